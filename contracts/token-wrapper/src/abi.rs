@@ -0,0 +1,267 @@
+// Solidity ABI encoding and decoding for the EVM calls the contract makes
+
+use cosmwasm_std::{Uint128, Uint256};
+use sha3::{Digest, Keccak256};
+
+use crate::error::TokenWrapperError;
+
+// Every ABI value occupies one or more 32 byte words
+const WORD: usize = 32;
+
+// Solidity types the contract encodes and decodes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    // The contract itself only decodes uint and bool return values
+    #[allow(dead_code)]
+    Address,
+    Uint,
+    Bool,
+    #[allow(dead_code)]
+    Bytes,
+    #[allow(dead_code)]
+    String,
+    #[allow(dead_code)]
+    Array(Box<ParamType>),
+}
+
+// A typed ABI value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Address([u8; 20]),
+    Uint(Uint256),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<Token>),
+}
+
+impl Token {
+    // Dynamic values are stored in the tail and referenced by offset from the head
+    fn is_dynamic(&self) -> bool {
+        matches!(self, Token::Bytes(_) | Token::String(_) | Token::Array(_))
+    }
+}
+
+// First four bytes of the keccak256 hash of a function or error signature such as
+// `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Encodes a function call: the selector followed by the encoded arguments
+pub fn encode_call(signature: &str, args: &[Token]) -> Vec<u8> {
+    let mut data = selector(signature).to_vec();
+    data.extend(encode(args));
+    data
+}
+
+// Encodes values as a tuple, the layout of function arguments and return values
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(tokens.len() * WORD);
+    let mut tail = vec![];
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend(uint_word(tokens.len() * WORD + tail.len()));
+            tail.extend(encode_value(token));
+        } else {
+            head.extend(encode_value(token));
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+fn encode_value(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Address(addr) => {
+            let mut word = vec![0u8; WORD - addr.len()];
+            word.extend_from_slice(addr);
+            word
+        },
+        Token::Uint(value) => value.to_be_bytes().to_vec(),
+        Token::Bool(value) => uint_word(usize::from(*value)),
+        Token::Bytes(bytes) => encode_bytes(bytes),
+        Token::String(string) => encode_bytes(string.as_bytes()),
+        Token::Array(items) => {
+            let mut data = uint_word(items.len());
+            data.extend(encode(items));
+            data
+        },
+    }
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut data = uint_word(bytes.len());
+    data.extend_from_slice(bytes);
+    data.resize(WORD + (bytes.len() + WORD - 1) / WORD * WORD, 0);
+    data
+}
+
+fn uint_word(value: usize) -> Vec<u8> {
+    Uint256::from(value as u128).to_be_bytes().to_vec()
+}
+
+// Decodes a tuple of the given types, e.g. a function's return data
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, TokenWrapperError> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, param)| match param {
+            ParamType::Address | ParamType::Uint | ParamType::Bool => decode_value(param, data, i * WORD),
+            _ => decode_value(param, data, read_usize(data, i * WORD)?),
+        })
+        .collect()
+}
+
+// Decodes the arguments of an encoded call after checking its selector
+#[cfg(test)]
+pub fn decode_call(signature: &str, types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, TokenWrapperError> {
+    match data {
+        [a, b, c, d, args @ ..] if [*a, *b, *c, *d] == selector(signature) => decode(types, args),
+        _ => Err(TokenWrapperError::InvalidAbiData(format!("expected a call to {signature}"))),
+    }
+}
+
+fn decode_value(param: &ParamType, data: &[u8], offset: usize) -> Result<Token, TokenWrapperError> {
+    match param {
+        ParamType::Address => {
+            let word = read_word(data, offset)?;
+            if word[..12].iter().any(|b| *b != 0) {
+                return Err(TokenWrapperError::InvalidAbiData("address has dirty upper bytes".to_string()));
+            }
+            Ok(Token::Address(word[12..].try_into()?))
+        },
+        ParamType::Uint => Ok(Token::Uint(Uint256::from_be_bytes(read_word(data, offset)?))),
+        ParamType::Bool => match Uint256::from_be_bytes(read_word(data, offset)?) {
+            value if value.is_zero() => Ok(Token::Bool(false)),
+            value if value == Uint256::one() => Ok(Token::Bool(true)),
+            _ => Err(TokenWrapperError::InvalidAbiData("bool is neither 0 nor 1".to_string())),
+        },
+        ParamType::Bytes => Ok(Token::Bytes(read_bytes(data, offset)?.to_vec())),
+        ParamType::String => String::from_utf8(read_bytes(data, offset)?.to_vec())
+            .map(Token::String)
+            .map_err(|_| TokenWrapperError::InvalidAbiData("string is not valid UTF-8".to_string())),
+        ParamType::Array(item) => {
+            let len = read_usize(data, offset)?;
+            let items = offset
+                .checked_add(WORD)
+                .and_then(|start| data.get(start..))
+                .ok_or_else(|| TokenWrapperError::InvalidAbiData("array out of bounds".to_string()))?;
+            // Every element takes at least one word, which bounds the allocation below
+            if len > items.len() / WORD {
+                return Err(TokenWrapperError::InvalidAbiData("array out of bounds".to_string()));
+            }
+            Ok(Token::Array(decode(&vec![item.as_ref().clone(); len], items)?))
+        },
+    }
+}
+
+fn read_word(data: &[u8], offset: usize) -> Result<[u8; WORD], TokenWrapperError> {
+    data.get(offset..offset.saturating_add(WORD))
+        .and_then(|word| word.try_into().ok())
+        .ok_or_else(|| TokenWrapperError::InvalidAbiData(format!("no word at offset {offset}")))
+}
+
+fn read_usize(data: &[u8], offset: usize) -> Result<usize, TokenWrapperError> {
+    let value = Uint256::from_be_bytes(read_word(data, offset)?);
+    Uint128::try_from(value)
+        .ok()
+        .and_then(|value| usize::try_from(value.u128()).ok())
+        .ok_or_else(|| TokenWrapperError::InvalidAbiData(format!("length or offset {value} out of range")))
+}
+
+fn read_bytes(data: &[u8], offset: usize) -> Result<&[u8], TokenWrapperError> {
+    let len = read_usize(data, offset)?;
+    offset
+        .checked_add(WORD)
+        .and_then(|start| data.get(start..start.saturating_add(len)))
+        .ok_or_else(|| TokenWrapperError::InvalidAbiData("bytes out of bounds".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calldata(hex: &str) -> Vec<u8> {
+        hex::decode(hex.split_whitespace().collect::<String>()).unwrap()
+    }
+
+    #[test]
+    fn selectors_match_known_functions() {
+        assert_eq!(hex::encode(selector("transfer(address,uint256)")), "a9059cbb");
+        assert_eq!(hex::encode(selector("transferFrom(address,address,uint256)")), "23b872dd");
+        assert_eq!(hex::encode(selector("balanceOf(address)")), "70a08231");
+        assert_eq!(hex::encode(selector("approve(address,uint256)")), "095ea7b3");
+    }
+
+    #[test]
+    fn static_call_round_trips() {
+        let recipient = [0x11u8; 20];
+        let expected = calldata(
+            "a9059cbb
+             0000000000000000000000001111111111111111111111111111111111111111
+             00000000000000000000000000000000000000000000000000000000000003e8",
+        );
+        let args = vec![Token::Address(recipient), Token::Uint(Uint256::from(1_000u128))];
+
+        assert_eq!(encode_call("transfer(address,uint256)", &args), expected);
+        assert_eq!(
+            decode_call("transfer(address,uint256)", &[ParamType::Address, ParamType::Uint], &expected).unwrap(),
+            args
+        );
+    }
+
+    // Example from the Solidity ABI specification
+    #[test]
+    fn dynamic_call_round_trips() {
+        let expected = calldata(
+            "a5643bf2
+             0000000000000000000000000000000000000000000000000000000000000060
+             0000000000000000000000000000000000000000000000000000000000000001
+             00000000000000000000000000000000000000000000000000000000000000a0
+             0000000000000000000000000000000000000000000000000000000000000004
+             6461766500000000000000000000000000000000000000000000000000000000
+             0000000000000000000000000000000000000000000000000000000000000003
+             0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000002
+             0000000000000000000000000000000000000000000000000000000000000003",
+        );
+        let args = vec![
+            Token::Bytes(b"dave".to_vec()),
+            Token::Bool(true),
+            Token::Array((1u128..=3).map(|i| Token::Uint(Uint256::from(i))).collect()),
+        ];
+        let types = [ParamType::Bytes, ParamType::Bool, ParamType::Array(Box::new(ParamType::Uint))];
+
+        assert_eq!(encode_call("sam(bytes,bool,uint256[])", &args), expected);
+        assert_eq!(decode_call("sam(bytes,bool,uint256[])", &types, &expected).unwrap(), args);
+    }
+
+    #[test]
+    fn string_round_trips() {
+        let args = vec![Token::String("Insufficient allowance".to_string())];
+        assert_eq!(decode(&[ParamType::String], &encode(&args)).unwrap(), args);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_data() {
+        let mut dirty_bool = encode(&[Token::Bool(true)]);
+        dirty_bool[0] = 1;
+        assert!(decode(&[ParamType::Bool], &dirty_bool).is_err());
+
+        let mut dirty_address = encode(&[Token::Address([0x22; 20])]);
+        dirty_address[11] = 1;
+        assert!(decode(&[ParamType::Address], &dirty_address).is_err());
+
+        let truncated = encode(&[Token::Bytes(vec![1u8; 40])]);
+        assert!(decode(&[ParamType::Bytes], &truncated[..truncated.len() - 32]).is_err());
+
+        assert!(decode_call("transfer(address,uint256)", &[ParamType::Address], &[0u8; 3]).is_err());
+
+        // Offsets pointing past the end of the address space are rejected, not overflowed
+        let huge_offset = uint_word(usize::MAX);
+        assert!(decode(&[ParamType::Bytes], &huge_offset).is_err());
+        assert!(decode(&[ParamType::Array(Box::new(ParamType::Uint))], &huge_offset).is_err());
+    }
+}
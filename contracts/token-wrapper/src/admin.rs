@@ -0,0 +1,392 @@
+use cosmwasm_std::{Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+use cw_utils::nonpayable;
+use sei_cosmwasm::{SeiMsg, SeiQueryWrapper};
+
+use crate::error::TokenWrapperError;
+use crate::fees::validate_fee_settings;
+use crate::msg::{AmountLimits, FeeSettings, PauseFlags, RegistrationMode, TokenType};
+use crate::registry::{denom_metadata, query_token_info, register_token};
+use crate::state::{Config, ACCRUED_FEES, CONFIG, TOKEN_INFO};
+
+// Ensures `sender` is the current owner. Every admin message goes through this check, so
+// once ownership is renounced the contract's configuration can no longer change.
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<Config, TokenWrapperError> {
+    let config = CONFIG.load(storage)?;
+    match &config.owner {
+        None => Err(TokenWrapperError::ContractImmutable),
+        Some(owner) if owner != sender => Err(TokenWrapperError::Unauthorized),
+        Some(_) => Ok(config),
+    }
+}
+
+// Ensures `sender` may toggle the pause switches, i.e. is either the owner or the pauser
+pub fn assert_pauser(storage: &dyn Storage, sender: &Addr) -> Result<Config, TokenWrapperError> {
+    let config = CONFIG.load(storage)?;
+    if config.owner.is_none() && config.pauser.is_none() {
+        return Err(TokenWrapperError::ContractImmutable);
+    }
+    if config.owner.as_ref() != Some(sender) && config.pauser.as_ref() != Some(sender) {
+        return Err(TokenWrapperError::Unauthorized);
+    }
+    Ok(config)
+}
+
+pub fn handle_propose_owner(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    let pending_owner = deps.api.addr_validate(&owner)?;
+    config.pending_owner = Some(pending_owner.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_owner")
+        .add_attribute("owner", info.sender)
+        .add_attribute("pending_owner", pending_owner))
+}
+
+pub fn handle_accept_ownership(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.pending_owner.as_ref() != Some(&info.sender) {
+        return Err(TokenWrapperError::Unauthorized);
+    }
+    let previous_owner = config.owner.replace(info.sender.clone());
+    config.pending_owner = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("previous_owner", previous_owner.map(String::from).unwrap_or_default())
+        .add_attribute("owner", info.sender))
+}
+
+pub fn handle_renounce_ownership(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    // Without an owner (or pauser) no admin message can pass its check again, so anything
+    // paused now would stay paused forever
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    let is_paused = |flags: &PauseFlags| flags.wrap || flags.unwrap;
+    let token_paused = TOKEN_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|entry| entry.map(|(_, registry)| is_paused(&registry.paused)))
+        .collect::<StdResult<Vec<_>>>()?
+        .contains(&true);
+    if is_paused(&config.paused) || token_paused {
+        return Err(TokenWrapperError::RenounceWhilePaused);
+    }
+    // Nobody could withdraw accrued fees afterwards, so they must go to a fee collector, along
+    // with everything accrued so far
+    let collector = config.fee_collector.clone().ok_or(TokenWrapperError::NoFeeCollector)?;
+    let accrued = ACCRUED_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|entry| entry.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    ACCRUED_FEES.clear(deps.storage);
+    config.owner = None;
+    config.pending_owner = None;
+    config.pauser = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "renounce_ownership")
+        .add_attribute("previous_owner", info.sender);
+    if !accrued.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: collector.to_string(),
+            amount: accrued,
+        });
+    }
+    Ok(response)
+}
+
+pub fn handle_set_registration_mode(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    mode: RegistrationMode,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    config.registration_mode = mode.clone();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_registration_mode")
+        .add_attribute("mode", format!("{mode:?}")))
+}
+
+pub fn handle_register_token(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: Env,
+    info: MessageInfo,
+    token_type: TokenType,
+    address: String,
+    subdenom: Option<String>,
+    wrapped_decimals: Option<u8>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    assert_owner(deps.storage, &info.sender)?;
+    let (registry, messages) = register_token(deps, &env, token_type, &address, subdenom, wrapped_decimals)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "register_token")
+        .add_attribute("token", registry.token_address)
+        .add_attribute("wrapped_denom", registry.wrapped_denom))
+}
+
+pub fn handle_update_denom_metadata(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    name: Option<String>,
+    symbol: Option<String>,
+    description: Option<String>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    assert_owner(deps.storage, &info.sender)?;
+    let registry = TOKEN_INFO
+        .may_load(deps.storage, &denom)?
+        .ok_or(TokenWrapperError::TokenNotRegistered)?;
+
+    // Refresh from the underlying token, then apply any overrides
+    let mut token_info = query_token_info(&deps.querier, &env, &registry.token_type, &registry.token_address)?;
+    if let Some(name) = name {
+        token_info.name = name;
+    }
+    if let Some(symbol) = symbol {
+        token_info.symbol = symbol;
+    }
+    let mut metadata = denom_metadata(&registry, &token_info)
+        .ok_or_else(|| TokenWrapperError::InvalidDenomMetadata("name and symbol must not be blank".to_string()))?;
+    if let Some(description) = description {
+        metadata.description = description;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_denom_metadata")
+        .add_attribute("denom", denom)
+        .add_attribute("name", metadata.name.clone())
+        .add_attribute("symbol", metadata.symbol.clone())
+        .add_message(SeiMsg::SetMetadata { metadata }))
+}
+
+pub fn handle_set_default_fees(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    fees: FeeSettings,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    validate_fee_settings(&fees)?;
+    config.default_fees = fees;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_default_fees")
+        .add_attribute("wrap_bps", config.default_fees.wrap_bps.to_string())
+        .add_attribute("unwrap_bps", config.default_fees.unwrap_bps.to_string()))
+}
+
+pub fn handle_set_token_fees(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    denom: String,
+    fees: Option<FeeSettings>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    assert_owner(deps.storage, &info.sender)?;
+    if let Some(fees) = &fees {
+        validate_fee_settings(fees)?;
+    }
+    let mut registry = TOKEN_INFO
+        .may_load(deps.storage, &denom)?
+        .ok_or(TokenWrapperError::TokenNotRegistered)?;
+    registry.fees = fees;
+    TOKEN_INFO.save(deps.storage, &denom, &registry)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_token_fees")
+        .add_attribute("denom", denom))
+}
+
+pub fn handle_set_fee_collector(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    collector: Option<String>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    config.fee_collector = collector.map(|collector| deps.api.addr_validate(&collector)).transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_collector")
+        .add_attribute("fee_collector", config.fee_collector.map(String::from).unwrap_or_default()))
+}
+
+pub fn handle_withdraw_fees(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    denoms: Vec<String>,
+    recipient: Option<String>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    assert_owner(deps.storage, &info.sender)?;
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+
+    let mut withdrawn = vec![];
+    for denom in denoms {
+        let amount = ACCRUED_FEES.may_load(deps.storage, &denom)?.unwrap_or_default();
+        if amount.is_zero() {
+            continue;
+        }
+        ACCRUED_FEES.remove(deps.storage, &denom);
+        withdrawn.push(Coin { denom, amount });
+    }
+    if withdrawn.is_empty() {
+        return Err(TokenWrapperError::NoFeesToWithdraw);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_fees")
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", withdrawn.iter().map(Coin::to_string).collect::<Vec<_>>().join(","))
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: withdrawn,
+        }))
+}
+
+pub fn validate_limits(limits: &AmountLimits) -> Result<(), TokenWrapperError> {
+    if let (Some(min), Some(max)) = (limits.min_amount, limits.max_amount) {
+        if min > max {
+            return Err(TokenWrapperError::InvalidLimits { min, max });
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_set_limits(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    limits: AmountLimits,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    validate_limits(&limits)?;
+    config.limits = limits;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_limits")
+        .add_attribute("min_amount", config.limits.min_amount.map(|min| min.to_string()).unwrap_or_default())
+        .add_attribute("max_amount", config.limits.max_amount.map(|max| max.to_string()).unwrap_or_default()))
+}
+
+pub fn handle_set_fee_on_transfer(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    denom: String,
+    enabled: bool,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    assert_owner(deps.storage, &info.sender)?;
+
+    let mut registry = TOKEN_INFO
+        .may_load(deps.storage, &denom)?
+        .ok_or(TokenWrapperError::TokenNotRegistered)?;
+    if registry.token_type != TokenType::ERC20 {
+        return Err(TokenWrapperError::InvalidTokenType);
+    }
+    registry.fee_on_transfer = enabled;
+    TOKEN_INFO.save(deps.storage, &denom, &registry)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_on_transfer")
+        .add_attribute("denom", denom)
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+pub fn handle_set_pauser(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    pauser: Option<String>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_owner(deps.storage, &info.sender)?;
+    config.pauser = pauser.map(|pauser| deps.api.addr_validate(&pauser)).transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pauser")
+        .add_attribute("pauser", config.pauser.map(String::from).unwrap_or_default()))
+}
+
+pub fn handle_set_paused(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    denom: Option<String>,
+    wrap: Option<bool>,
+    unwrap: Option<bool>,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let mut config = assert_pauser(deps.storage, &info.sender)?;
+    let paused = match &denom {
+        Some(denom) => {
+            let mut registry = TOKEN_INFO
+                .may_load(deps.storage, denom)?
+                .ok_or(TokenWrapperError::TokenNotRegistered)?;
+            apply_pause_flags(&mut registry.paused, wrap, unwrap);
+            TOKEN_INFO.save(deps.storage, denom, &registry)?;
+            registry.paused
+        },
+        None => {
+            apply_pause_flags(&mut config.paused, wrap, unwrap);
+            CONFIG.save(deps.storage, &config)?;
+            config.paused
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "set_paused")
+        .add_attribute("denom", denom.unwrap_or_else(|| "global".to_string()))
+        .add_attribute("wrap_paused", paused.wrap.to_string())
+        .add_attribute("unwrap_paused", paused.unwrap.to_string()))
+}
+
+fn apply_pause_flags(flags: &mut PauseFlags, wrap: Option<bool>, unwrap: Option<bool>) {
+    if let Some(wrap) = wrap {
+        flags.wrap = wrap;
+    }
+    if let Some(unwrap) = unwrap {
+        flags.unwrap = unwrap;
+    }
+}
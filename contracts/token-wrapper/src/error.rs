@@ -1,4 +1,4 @@
-use cosmwasm_std::{Binary, StdError};
+use cosmwasm_std::{Binary, StdError, Uint128, Uint256};
 use cw_utils::PaymentError;
 use std::array::TryFromSliceError;
 
@@ -28,6 +28,9 @@ pub enum TokenWrapperError {
     #[error("The recipient address must be 20 bytes long")]
     InvalidRecipient,
 
+    #[error("The recipient override for {0} must name a sent denom, once")]
+    InvalidRecipientOverride(String),
+
     #[error("The reply id {0} is invalid")]
     InvalidReplyId(u64),
 
@@ -42,7 +45,82 @@ pub enum TokenWrapperError {
 
     #[error("Token not registered in this contract")]
     TokenNotRegistered,
-    
+
+    #[error("Token {0} is already registered")]
+    TokenAlreadyRegistered(String),
+
+    #[error("Wrapped denom {0} already belongs to another token")]
+    DenomCollision(String),
+
+    #[error("Invalid subdenom {0}")]
+    InvalidSubdenom(String),
+
+    #[error("Invalid denom metadata: {0}")]
+    InvalidDenomMetadata(String),
+
+    #[error("Invalid ABI data: {0}")]
+    InvalidAbiData(String),
+
+    #[error("Amount {0} exceeds the range of a native token amount")]
+    AmountExceedsNativeRange(Uint256),
+
+    #[error("Wrapped decimals {wrapped_decimals} must not exceed the token's {decimals} decimals")]
+    InvalidDecimals { decimals: u8, wrapped_decimals: u8 },
+
+    #[error("Amount {amount} is not a multiple of the scale factor {factor}")]
+    AmountNotScalable { amount: Uint256, factor: Uint256 },
+
+    #[error("Insufficient {token} allowance: {allowance}, needed {amount}")]
+    InsufficientAllowance { token: String, amount: Uint256, allowance: Uint256 },
+
+    #[error("Insufficient {token} balance: {balance}, needed {amount}")]
+    InsufficientBalance { token: String, amount: Uint256, balance: Uint256 },
+
+    #[error("The address {0} has no associated EVM address")]
+    NoAssociatedEvmAddress(String),
+
+    #[error("The caller is not authorized to wrap tokens from EVM account {0}")]
+    UnauthorizedEvmSender(String),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Ownership has been renounced, the contract configuration is immutable")]
+    ContractImmutable,
+
+    #[error("A fee collector must be set before ownership is renounced")]
+    NoFeeCollector,
+
+    #[error("Ownership cannot be renounced while wrapping or unwrapping is paused")]
+    RenounceWhilePaused,
+
+    #[error("The {0} operation is currently paused")]
+    Paused(String),
+
+    #[error("Invalid fee settings: {0}")]
+    InvalidFee(String),
+
+    #[error("Invalid limits: minimum {min} is above maximum {max}")]
+    InvalidLimits { min: Uint128, max: Uint128 },
+
+    #[error("The amount {0} is outside the allowed limits")]
+    AmountOutOfLimits(Uint128),
+
+    #[error("There are no accrued fees to withdraw")]
+    NoFeesToWithdraw,
+
+    #[error("The contract did not receive any tokens from the transfer")]
+    NoTokensReceived,
+
+    #[error("Cannot migrate from contract {actual}, expected {expected}")]
+    InvalidContractName { expected: String, actual: String },
+
+    #[error("Cannot migrate from version {stored} down to {new}")]
+    CannotDowngrade { stored: String, new: String },
+
+    #[error("Invalid contract version: {0}")]
+    InvalidVersion(#[from] semver::Error),
+
     #[error("Invalid conversion: {0}")]
     TryFromSliceError(#[from] TryFromSliceError),
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+use cosmwasm_std::{Addr, Binary, CanonicalAddr, Deps, DepsMut, Env, QuerierWrapper, StdResult, Uint128, Uint256};
+use sei_cosmwasm::{SeiQuerier, SeiQueryWrapper};
+use sha3::{Digest, Keccak256};
+
+use crate::abi::{self, ParamType, Token};
+use crate::error::TokenWrapperError;
+use crate::state::CONFIG;
+
+// Length of a `0x`-prefixed hex EVM address
+const HEX_ADDRESS_LENGTH: usize = 42;
+
+// Resolves the EVM address associated with a Sei address
+pub fn associated_evm_address(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    sei_addr: &Addr,
+) -> Result<String, TokenWrapperError> {
+    let res = SeiQuerier::new(querier).get_evm_address(sei_addr.to_string())?;
+    if !res.associated || res.evm_address.is_empty() {
+        return Err(TokenWrapperError::NoAssociatedEvmAddress(sei_addr.to_string()));
+    }
+    Ok(res.evm_address)
+}
+
+// Looks up the EVM address Sei associates with this contract, if there is one yet
+pub fn lookup_contract_evm_address(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+) -> StdResult<Option<String>> {
+    let res = SeiQuerier::new(querier).get_evm_address(env.contract.address.to_string())?;
+    if !res.associated || res.evm_address.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(res.evm_address))
+}
+
+// Returns the contract's own EVM address, caching it in the config the first time
+// the association is found
+pub fn contract_evm_address(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: &Env,
+) -> Result<[u8; 20], TokenWrapperError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let evm_addr = match config.contract_evm_address {
+        Some(evm_addr) => evm_addr,
+        None => {
+            let evm_addr = lookup_contract_evm_address(&deps.querier, env)?
+                .ok_or_else(|| TokenWrapperError::NoAssociatedEvmAddress(env.contract.address.to_string()))?;
+            config.contract_evm_address = Some(evm_addr.clone());
+            CONFIG.save(deps.storage, &config)?;
+            evm_addr
+        }
+    };
+    parse_evm_address(&evm_addr)
+}
+
+// Resolves the Sei address associated with an EVM address, if any
+pub fn associated_sei_address(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    evm_addr: &str,
+) -> Result<Option<String>, TokenWrapperError> {
+    let res = SeiQuerier::new(querier).get_sei_address(evm_addr.to_string())?;
+    if !res.associated || res.sei_address.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(res.sei_address))
+}
+
+// Returns the Sei address that receives native tokens sent to an EVM address: the associated
+// address if there is one, otherwise the address derived from the same 20 bytes, which Sei
+// merges into the associated account once the EVM address is associated. A mistyped address
+// would still derive some account, so mixed-case input must carry a valid EIP-55 checksum.
+pub fn sei_address_for_evm(
+    deps: Deps<SeiQueryWrapper>,
+    evm_addr: &str,
+) -> Result<Addr, TokenWrapperError> {
+    let bytes = parse_checksummed_evm_address(evm_addr)?;
+    match associated_sei_address(&deps.querier, evm_addr)? {
+        Some(sei_addr) => Ok(deps.api.addr_validate(&sei_addr)?),
+        None => Ok(deps.api.addr_humanize(&CanonicalAddr::from(bytes.as_slice()))?),
+    }
+}
+
+// Queries an ERC20 balance through a `balanceOf` static call, which returns the full uint256
+// value where the ERC20 balance query is limited to 128 bits
+pub fn erc20_balance(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_addr: &str,
+    owner: [u8; 20],
+) -> Result<Uint256, TokenWrapperError> {
+    erc20_uint_call(querier, env, token_addr, "balanceOf(address)", &[Token::Address(owner)])
+}
+
+// Queries how much `spender` may transfer out of `owner` through an `allowance` static call
+pub fn erc20_allowance(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_addr: &str,
+    owner: [u8; 20],
+    spender: [u8; 20],
+) -> Result<Uint256, TokenWrapperError> {
+    let args = [Token::Address(owner), Token::Address(spender)];
+    erc20_uint_call(querier, env, token_addr, "allowance(address,address)", &args)
+}
+
+fn erc20_uint_call(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_addr: &str,
+    signature: &str,
+    args: &[Token],
+) -> Result<Uint256, TokenWrapperError> {
+    let res = SeiQuerier::new(querier).static_call(
+        env.contract.address.to_string(),
+        token_addr.to_string(),
+        Binary::from(abi::encode_call(signature, args)).to_base64(),
+    )?;
+    match abi::decode(&[ParamType::Uint], &Binary::from_base64(&res.encoded_data)?)?.as_slice() {
+        [Token::Uint(value)] => Ok(*value),
+        _ => Err(TokenWrapperError::InvalidAbiData(format!("expected a uint256 from {signature}"))),
+    }
+}
+
+// Converts an ERC20 amount to a native amount, failing rather than truncating when it does
+// not fit
+pub fn to_native_amount(amount: Uint256) -> Result<Uint128, TokenWrapperError> {
+    Uint128::try_from(amount).map_err(|_| TokenWrapperError::AmountExceedsNativeRange(amount))
+}
+
+// Parses a `0x`-prefixed hex string into raw EVM address bytes
+pub fn parse_evm_address(evm_addr: &str) -> Result<[u8; 20], TokenWrapperError> {
+    let hex_part = evm_addr
+        .strip_prefix("0x")
+        .ok_or_else(|| TokenWrapperError::InvalidEvmAddress(evm_addr.to_string()))?;
+    hex::FromHex::from_hex(hex_part)
+        .map_err(|_| TokenWrapperError::InvalidEvmAddress(evm_addr.to_string()))
+}
+
+// The canonical form an ERC20 address is stored and looked up under: `0x` followed by
+// lowercase hex
+pub fn canonical_erc20_address(evm_addr: &str) -> Result<String, TokenWrapperError> {
+    Ok(format_evm_address(&parse_evm_address(evm_addr)?))
+}
+
+// Parses a `0x`-prefixed hex string, enforcing the EIP-55 checksum when it is mixed case
+pub fn parse_checksummed_evm_address(evm_addr: &str) -> Result<[u8; 20], TokenWrapperError> {
+    let bytes = parse_evm_address(evm_addr)?;
+    let hex_part = &evm_addr[2..];
+    let mixed_case = hex_part.chars().any(|c| c.is_ascii_lowercase()) && hex_part.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && hex_part != &to_checksum_address(&bytes)[2..] {
+        return Err(TokenWrapperError::InvalidEvmAddress(evm_addr.to_string()));
+    }
+    Ok(bytes)
+}
+
+// Formats raw EVM address bytes with the EIP-55 mixed-case checksum
+pub fn to_checksum_address(evm_addr: &[u8; 20]) -> String {
+    let lower = hex::encode(evm_addr);
+    let hash = Keccak256::digest(lower.as_bytes());
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+// Resolves an EVM recipient given as a `0x` hex address, a Sei address with an associated
+// EVM address, or the base64 encoding of the raw 20 bytes. Base64 of bytes starting with
+// 0xD31 also begins with "0x", so the two encodings are told apart by length.
+pub fn resolve_evm_recipient(
+    deps: Deps<SeiQueryWrapper>,
+    recipient: &str,
+) -> Result<[u8; 20], TokenWrapperError> {
+    if recipient.starts_with("0x") && recipient.len() == HEX_ADDRESS_LENGTH {
+        return parse_checksummed_evm_address(recipient);
+    }
+    if let Ok(sei_addr) = deps.api.addr_validate(recipient) {
+        return parse_evm_address(&associated_evm_address(&deps.querier, &sei_addr)?);
+    }
+    Binary::from_base64(recipient)
+        .ok()
+        .and_then(|bytes| <[u8; 20]>::try_from(bytes.as_slice()).ok())
+        .ok_or(TokenWrapperError::InvalidRecipient)
+}
+
+// Formats raw EVM address bytes as a `0x`-prefixed hex string
+pub fn format_evm_address(evm_addr: &[u8; 20]) -> String {
+    format!("0x{}", hex::encode(evm_addr))
+}
@@ -1,20 +1,30 @@
 use cosmwasm_std::{
-    BankMsg, Binary, Coin, CosmosMsg, DepsMut, Env, MessageInfo, ReplyOn, Response, SubMsg, to_json_binary,
-    Uint128, WasmMsg, Addr,
+    BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, QuerierWrapper, ReplyOn, Response,
+    Storage, SubMsg, from_json, to_json_binary, Uint128, Uint256, WasmMsg, Addr,
 };
-use sei_cosmwasm::{SeiMsg, SeiQueryWrapper, SeiQuerier};
+use sei_cosmwasm::{SeiMsg, SeiQueryWrapper};
 use cw_utils::nonpayable;
 
+use crate::abi::{self, Token};
 use crate::error::TokenWrapperError;
-use crate::msg::TokenType;
-use crate::state::{TOKEN_INFO, ERC20_TO_DENOM, CW20_TO_DENOM, token_addr_to_subdenom, TokenRegistry, WRAP_EVM_CALL_ID, UNWRAP_EVM_CALL_ID};
+use crate::evm::{
+    associated_evm_address, associated_sei_address, canonical_erc20_address, contract_evm_address, format_evm_address, parse_evm_address,
+    erc20_allowance, erc20_balance, resolve_evm_recipient, sei_address_for_evm, to_native_amount,
+};
+use crate::fees::{collect_fee, compute_fee, effective_fees, FeeAction};
+use crate::msg::{AmountLimits, ReceiveMsg, TokenType, UnwrapRecipient};
+use crate::registry::{load_or_register, to_underlying_amount, to_wrapped_amount};
+use crate::state::{
+    CONFIG, TOKEN_INFO, WRAP_OPERATORS, record_lock, record_release, save_pending_op, PendingOp, PendingTransfer,
+    PendingWrap, TokenRegistry,
+};
 
 // ERC20 function signatures
-const TRANSFER_SIG: &[u8] = b"\xa9\x05\x9c\xbb";
-const TRANSFER_FROM_SIG: &[u8] = b"\x23\xb8\x72\xdd";
+const TRANSFER_SIG: &str = "transfer(address,uint256)";
+const TRANSFER_FROM_SIG: &str = "transferFrom(address,address,uint256)";
 
 pub fn handle_cw20_receive(
-    deps: DepsMut<SeiQueryWrapper>,
+    mut deps: DepsMut<SeiQueryWrapper>,
     env: Env,
     info: MessageInfo,
     cw20_msg: cw20::Cw20ReceiveMsg,
@@ -24,135 +34,110 @@ pub fn handle_cw20_receive(
         return Err(TokenWrapperError::UnfundedCall);
     }
 
-    // Extract recipient - either explicitly provided in message or sender of the CW20 token
-    let recipient_addr = match String::from_utf8(cw20_msg.msg.clone().to_vec()) {
-        Ok(addr_str) => {
-            if let Ok(validated_addr) = deps.api.addr_validate(&addr_str) {
-                validated_addr
-            } else {
-                deps.api.addr_validate(&cw20_msg.sender)?
-            }
-        },
-        Err(_) => deps.api.addr_validate(&cw20_msg.sender)?,
+    // Decode where the wrapped tokens go. An empty message wraps to the CW20 sender, anything
+    // else must be a valid `ReceiveMsg`.
+    let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+    let (delivery, memo) = if cw20_msg.msg.is_empty() {
+        (Delivery::Send(sender), None)
+    } else {
+        match from_json::<ReceiveMsg>(&cw20_msg.msg)? {
+            ReceiveMsg::Wrap { recipient, memo } => {
+                let recipient = match recipient {
+                    Some(recipient) => deps.api.addr_validate(&recipient)?,
+                    None => sender,
+                };
+                (Delivery::Send(recipient), memo)
+            },
+            ReceiveMsg::WrapToEvm { evm_address, memo } => {
+                (Delivery::Send(sei_address_for_evm(deps.as_ref(), &evm_address)?), memo)
+            },
+            ReceiveMsg::WrapAndCall { contract, msg, memo } => {
+                (Delivery::Call { contract: deps.api.addr_validate(&contract)?, msg }, memo)
+            },
+        }
     };
 
     // Get or register the CW20 token
     let token_addr = info.sender.clone();
-    let subdenom = token_addr_to_subdenom(&TokenType::CW20, &token_addr.to_string());
-    let denom = format!("factory/{}/{}", env.contract.address, subdenom);
-    
-    // Check if we've seen this token before
-    let mut response = Response::new();
-    if !CW20_TO_DENOM.has(deps.storage, &token_addr) {
-        // Verify this is a valid CW20 token
-        let _token_info = deps.querier.query_wasm_smart::<cw20::TokenInfoResponse>(
-            token_addr.clone(), 
-            &cw20::Cw20QueryMsg::TokenInfo {}
-        )?;
-        
-        // Register the token
-        CW20_TO_DENOM.save(deps.storage, &token_addr, &denom)?;
-        
-        // Save token info
-        TOKEN_INFO.save(deps.storage, &denom, &TokenRegistry {
-            token_type: TokenType::CW20,
-            token_address: token_addr.to_string(),
-            wrapped_denom: denom.clone(),
-        })?;
-        
-        // Create the denom
-        response = response.add_message(SeiMsg::CreateDenom { subdenom });
-    }
-
-    // Create the wrapped token
-    let amount = Coin {
-        denom: denom.clone(),
-        amount: cw20_msg.amount,
-    };
+    let (registry, messages) = load_or_register(deps.branch(), &env, TokenType::CW20, token_addr.as_str())?;
+    assert_not_paused(deps.storage, &registry.wrapped_denom, PauseAction::Wrap)?;
+    let amount = to_wrapped_amount(&registry, cw20_msg.amount.into())?;
+    assert_within_limits(&CONFIG.load(deps.storage)?.limits, amount)?;
+    let mut response = Response::new().add_messages(messages);
 
-    // Mint and send tokens
+    // Mint the wrapped tokens and deliver them minus the wrap fee
+    record_lock(deps.storage, &registry.wrapped_denom, cw20_msg.amount.into(), amount)?;
+    let (messages, fee) = mint_wrapped(deps.storage, &registry, amount, &delivery)?;
     response = response
-        .add_message(SeiMsg::MintTokens { amount: amount.clone() })
-        .add_submessage(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            amount: vec![amount],
-            to_address: recipient_addr.to_string(),
-        })))
+        .add_messages(messages)
         .add_attribute("action", "wrap_cw20")
         .add_attribute("token", token_addr.to_string())
         .add_attribute("amount", cw20_msg.amount.to_string())
-        .add_attribute("recipient", recipient_addr.to_string());
+        .add_attribute("wrapped_amount", amount.to_string())
+        .add_attribute("fee", fee.to_string())
+        .add_attribute("recipient", delivery.recipient().to_string());
+    if let Some(memo) = memo {
+        response = response.add_attribute("memo", memo);
+    }
 
     Ok(response)
 }
 
 pub fn handle_wrap_erc20(
-    deps: DepsMut<SeiQueryWrapper>,
+    mut deps: DepsMut<SeiQueryWrapper>,
     env: Env,
     info: MessageInfo,
-    evm_sender: Binary,
+    evm_sender: Option<Binary>,
     token_addr: String,
-    amount: Uint128,
+    amount: Uint256,
     recipient: Option<Addr>,
 ) -> Result<Response<SeiMsg>, TokenWrapperError> {
     // Ensure no native tokens were sent
     nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
 
-    // Validate EVM address format
-    if !token_addr.starts_with("0x") {
-        return Err(TokenWrapperError::InvalidEvmAddress(token_addr));
-    }
-
-    // Query token info to validate it's a real ERC20
-    let querier = SeiQuerier::new(&deps.querier);
-    querier
-        .erc20_token_info(token_addr.clone(), env.contract.address.to_string())
-        .map_err(|err| TokenWrapperError::InvalidERC20Contract(err))?;
+    // Validate EVM address format, so every spelling of the address maps to the same token
+    let token_addr = canonical_erc20_address(&token_addr)?;
 
-    // Generate standardized token address and denom
-    let capped_tkn_addr = token_addr[2..].to_uppercase();
-    // Fix: Add underscore prefix to unused variable
-    let _bare_addr: [u8; 20] = hex::FromHex::from_hex(&capped_tkn_addr)
-        .map_err(|_| TokenWrapperError::InvalidEvmAddress(token_addr.clone()))?;
+    // Resolve the EVM account the tokens are pulled from
+    let evm_owner = resolve_evm_owner(deps.as_ref(), &info.sender, evm_sender)?;
 
-    let subdenom = token_addr_to_subdenom(&TokenType::ERC20, &token_addr);
-    let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+    // Get or register the ERC20 token
+    let (registry, messages) = load_or_register(deps.branch(), &env, TokenType::ERC20, &token_addr)?;
+    assert_not_paused(deps.storage, &registry.wrapped_denom, PauseAction::Wrap)?;
+    // The amount must convert exactly into a native wrapped amount
+    let wrapped_amount = to_wrapped_amount(&registry, amount)?;
+    assert_within_limits(&CONFIG.load(deps.storage)?.limits, wrapped_amount)?;
+    let mut response = Response::new().add_messages(messages);
+    let contract_addr = contract_evm_address(deps.branch(), &env)?;
+    assert_erc20_allowance(&deps.querier, &env, &token_addr, evm_owner, contract_addr, amount)?;
+    assert_erc20_balance(&deps.querier, &env, &token_addr, evm_owner, amount)?;
 
-    // Check if we've seen this token before
-    let mut response = Response::new();
-    if !ERC20_TO_DENOM.has(deps.storage, &token_addr) {
-        // Register the token
-        ERC20_TO_DENOM.save(deps.storage, &token_addr, &denom)?;
-        
-        // Save token info
-        TOKEN_INFO.save(deps.storage, &denom, &TokenRegistry {
-            token_type: TokenType::ERC20,
-            token_address: token_addr.clone(),
-            wrapped_denom: denom.clone(),
-        })?;
-        
-        // Create the denom
-        response = response.add_message(SeiMsg::CreateDenom { subdenom });
-    }
+    // Fee-on-transfer tokens are minted from the measured balance change instead of `amount`
+    let balance_before = if registry.fee_on_transfer {
+        Some(erc20_balance(&deps.querier, &env, &token_addr, contract_addr)?)
+    } else {
+        None
+    };
 
     // Create EVM payload for transferFrom
     let recipient_addr = recipient.unwrap_or(info.sender);
-    let contract_addr: [u8; 20] = match env.contract.address.as_bytes().try_into() {
-        Ok(addr) => addr,
-        Err(_) => return Err(TokenWrapperError::InvalidRecipient),
-    };
-    
-    let payload = encode_transfer_from_payload(evm_sender, contract_addr, amount)?;
-    
-    // Create the coin to mint
-    let amount_coin = Coin {
-        denom: denom.clone(),
+
+    let payload = encode_transfer_from_payload(evm_owner, contract_addr, amount);
+
+    // Record the wrap so the tokens are only minted once the transfer is confirmed
+    let reply_id = save_pending_op(deps.storage, &PendingOp::Wrap(PendingWrap {
+        token_address: token_addr.clone(),
+        wrapped_denom: registry.wrapped_denom,
         amount,
-    };
+        recipient: recipient_addr.clone(),
+        balance_before,
+        evm_owner: format_evm_address(&evm_owner),
+    }))?;
 
     // Construct the full response
     response = response
         .add_submessage(SubMsg {
-            id: WRAP_EVM_CALL_ID,
+            id: reply_id,
             msg: SeiMsg::CallEvm {
                 to: token_addr.clone(),
                 data: payload,
@@ -162,38 +147,47 @@ pub fn handle_wrap_erc20(
             gas_limit: None,
             reply_on: ReplyOn::Always,
         })
-        .add_message(SeiMsg::MintTokens { amount: amount_coin.clone() })
-        .add_submessage(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            amount: vec![amount_coin],
-            to_address: recipient_addr.to_string(),
-        })))
         .add_attribute("action", "wrap_erc20")
         .add_attribute("token", token_addr)
+        .add_attribute("evm_sender", format_evm_address(&evm_owner))
         .add_attribute("amount", amount.to_string())
-        .add_attribute("recipient", recipient_addr.to_string());
+        .add_attribute("wrapped_amount", wrapped_amount.to_string())
+        .add_attribute("recipient", recipient_addr.to_string())
+        .add_attribute("reply_id", reply_id.to_string());
 
     Ok(response)
 }
 
 pub fn handle_unwrap(
-    deps: DepsMut<SeiQueryWrapper>,
+    mut deps: DepsMut<SeiQueryWrapper>,
     env: Env,
     info: MessageInfo,
-    token_type: TokenType,
-    evm_recipient: Option<Binary>,
+    token_type: Option<TokenType>,
+    evm_recipient: Option<String>,
     cosmos_recipient: Option<Addr>,
+    recipients: Vec<UnwrapRecipient>,
 ) -> Result<Response<SeiMsg>, TokenWrapperError> {
     // Ensure tokens were sent
     if info.funds.is_empty() {
         return Err(TokenWrapperError::UnfundedCall);
     }
+    // Every override must name exactly one of the sent denoms
+    for (i, entry) in recipients.iter().enumerate() {
+        if !info.funds.iter().any(|fund| fund.denom == entry.denom)
+            || recipients[..i].iter().any(|other| other.denom == entry.denom)
+        {
+            return Err(TokenWrapperError::InvalidRecipientOverride(entry.denom.clone()));
+        }
+    }
 
+    let config = CONFIG.load(deps.storage)?;
     let mut response = Response::new();
     
     // Process each sent token
     for fund in info.funds {
-        // Split denom to validate it belongs to this contract
-        let parts: Vec<&str> = fund.denom.split('/').collect();
+        // Split denom to validate it belongs to this contract. Subdenom aliases may contain `/`
+        // themselves, so only the prefix is split off.
+        let parts: Vec<&str> = fund.denom.splitn(3, '/').collect();
         if parts.len() != 3 || parts[0] != "factory" || parts[1] != env.contract.address.as_str() {
             return Err(TokenWrapperError::TokenDoesntBelongToContract);
         }
@@ -201,50 +195,67 @@ pub fn handle_unwrap(
         // Look up token info
         let token_info = TOKEN_INFO.load(deps.storage, &fund.denom)
             .map_err(|_| TokenWrapperError::TokenDoesntBelongToContract)?;
+        assert_not_paused(deps.storage, &fund.denom, PauseAction::Unwrap)?;
+        assert_within_limits(&config.limits, fund.amount)?;
             
-        // Verify token type matches
-        if token_info.token_type != token_type {
+        // Verify token type matches, if the caller asserted one
+        if token_type.as_ref().is_some_and(|token_type| *token_type != token_info.token_type) {
             return Err(TokenWrapperError::InvalidTokenType);
         }
+        let recipient_override = recipients
+            .iter()
+            .find(|entry| entry.denom == fund.denom)
+            .map(|entry| entry.recipient.as_str());
+
+        // The unwrap fee stays wrapped and goes to the fee collector, only the rest is returned
+        let fee = compute_fee(effective_fees(&config, &token_info), FeeAction::Unwrap, fund.amount);
+        let returned = Coin {
+            denom: fund.denom.clone(),
+            amount: fund.amount - fee,
+        };
+        let fee = Coin {
+            denom: fund.denom.clone(),
+            amount: fee,
+        };
+        let underlying_amount = to_underlying_amount(&token_info, returned.amount)?;
+        record_release(deps.storage, &fund.denom, underlying_amount, returned.amount)?;
+        response = response
+            .add_messages(collect_fee(deps.storage, &config, fee.clone())?)
+            .add_attribute("fee", fee.to_string())
+            .add_attribute("underlying_amount", underlying_amount.to_string());
         
-        // Handle unwrapping based on token type
-        match token_type {
+        // Handle unwrapping based on the type of the underlying token
+        match token_info.token_type {
             TokenType::ERC20 => {
-                // For ERC20, we need an EVM recipient
-                let evm_addr = match evm_recipient.clone() {
-                    Some(addr) => {
-                        if addr.len() != 20 {
-                            return Err(TokenWrapperError::InvalidRecipient);
-                        }
-                        addr
-                    },
-                    None => return Err(TokenWrapperError::MissingRecipient),
+                // For ERC20, we need an EVM recipient, by default the sender's associated address
+                let evm_recipient_array: [u8; 20] = match recipient_override.or(evm_recipient.as_deref()) {
+                    Some(addr) => resolve_evm_recipient(deps.as_ref(), addr)?,
+                    // Default to the sender's own associated EVM address
+                    None => parse_evm_address(&associated_evm_address(&deps.querier, &info.sender)?)?,
                 };
                 
-                // Encode transfer call
-                let evm_recipient_array: [u8; 20] = evm_addr.as_slice().try_into()?;
-                let evm_payload = encode_transfer_payload(evm_recipient_array, fund.amount);
-                
-                // Add unwrap operation
+                // The contract must still hold the tokens it is about to return
+                let contract_addr = contract_evm_address(deps.branch(), &env)?;
+                let token_addr = &token_info.token_address;
+                assert_erc20_balance(&deps.querier, &env, token_addr, contract_addr, underlying_amount)?;
+
+                // Add unwrap operation, tracked under its own reply id
+                let transfer = PendingTransfer {
+                    token_address: token_info.token_address,
+                    wrapped_denom: fund.denom.clone(),
+                    amount: underlying_amount,
+                    recipient: format_evm_address(&evm_recipient_array),
+                };
                 response = response
-                    .add_submessage(SubMsg {
-                        id: UNWRAP_EVM_CALL_ID,
-                        msg: SeiMsg::CallEvm {
-                            to: token_info.token_address,
-                            data: evm_payload,
-                            value: Uint128::zero(),
-                        }
-                        .into(),
-                        gas_limit: None,
-                        reply_on: ReplyOn::Always,
-                    })
-                    .add_message(SeiMsg::BurnTokens { amount: fund.clone() });
+                    .add_submessage(erc20_transfer(deps.storage, transfer, PendingOp::Unwrap)?)
+                    .add_message(SeiMsg::BurnTokens { amount: returned.clone() });
             },
             TokenType::CW20 => {
-                // For CW20, we need a Cosmos recipient
-                let recipient = match cosmos_recipient.clone() {
-                    Some(addr) => addr,
-                    None => return Err(TokenWrapperError::MissingRecipient),
+                // For CW20, we need a Cosmos recipient, by default the sender
+                let recipient = match (recipient_override, &cosmos_recipient) {
+                    (Some(addr), _) => deps.api.addr_validate(addr)?,
+                    (None, Some(addr)) => addr.clone(),
+                    (None, None) => info.sender.clone(),
                 };
                 
                 // Add unwrap operation
@@ -252,12 +263,12 @@ pub fn handle_unwrap(
                     .add_submessage(SubMsg::new(WasmMsg::Execute {
                         contract_addr: token_info.token_address,
                         msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
-                            amount: fund.amount,
+                            amount: to_native_amount(underlying_amount)?,
                             recipient: recipient.to_string(),
                         })?,
                         funds: vec![],
                     }))
-                    .add_message(SeiMsg::BurnTokens { amount: fund.clone() });
+                    .add_message(SeiMsg::BurnTokens { amount: returned.clone() });
             },
         }
     }
@@ -266,37 +277,207 @@ pub fn handle_unwrap(
     Ok(response)
 }
 
-// Helper function to encode ERC20 transferFrom call
-fn encode_transfer_from_payload(
-    owner: Binary,
-    recipient: [u8; 20],
-    amount: Uint128
-) -> Result<String, TokenWrapperError> {
-    if owner.len() != 20 {
-        return Err(TokenWrapperError::InvalidEvmAddress(format!("0x{}", hex::encode(owner))));
+// Sends an ERC20 `transfer` out of the contract, recording it so its reply can be matched
+// back to the transfer
+pub fn erc20_transfer(
+    storage: &mut dyn Storage,
+    transfer: PendingTransfer,
+    op: fn(PendingTransfer) -> PendingOp,
+) -> Result<SubMsg<SeiMsg>, TokenWrapperError> {
+    let msg = SeiMsg::CallEvm {
+        to: transfer.token_address.clone(),
+        data: encode_transfer_payload(parse_evm_address(&transfer.recipient)?, transfer.amount),
+        value: Uint128::zero(),
+    };
+    let reply_id = save_pending_op(storage, &op(transfer))?;
+
+    Ok(SubMsg {
+        id: reply_id,
+        msg: msg.into(),
+        gas_limit: None,
+        reply_on: ReplyOn::Always,
+    })
+}
+
+// Where freshly minted wrapped tokens are delivered
+pub enum Delivery {
+    // Plain bank transfer to the recipient
+    Send(Addr),
+    // Sent as funds along with an execute message to a contract
+    Call { contract: Addr, msg: Binary },
+}
+
+impl Delivery {
+    pub fn recipient(&self) -> &Addr {
+        match self {
+            Delivery::Send(recipient) => recipient,
+            Delivery::Call { contract, .. } => contract,
+        }
     }
+}
 
-    let mut buff = Vec::with_capacity(100);
-    buff.extend_from_slice(TRANSFER_FROM_SIG);
-    buff.extend_from_slice(&[0; 12]);
-    buff.extend_from_slice(owner.as_slice());
-    buff.extend_from_slice(&[0; 12]);
-    buff.extend_from_slice(&recipient);
-    buff.extend_from_slice(&[0; 16]);
-    buff.extend_from_slice(&amount.to_be_bytes());
+// Mints `amount` of a wrapped denom and delivers it minus the wrap fee. Returns the
+// messages to dispatch along with the fee charged.
+pub fn mint_wrapped(
+    storage: &mut dyn Storage,
+    registry: &TokenRegistry,
+    amount: Uint128,
+    delivery: &Delivery,
+) -> Result<(Vec<CosmosMsg<SeiMsg>>, Uint128), TokenWrapperError> {
+    let config = CONFIG.load(storage)?;
+    let fee = compute_fee(effective_fees(&config, registry), FeeAction::Wrap, amount);
+    let delivered = Coin { denom: registry.wrapped_denom.clone(), amount: amount - fee };
 
-    Ok(Binary::from(buff).to_base64())
+    let mut messages: Vec<CosmosMsg<SeiMsg>> = vec![
+        SeiMsg::MintTokens { amount: Coin { denom: registry.wrapped_denom.clone(), amount } }.into(),
+    ];
+    messages.push(match delivery {
+        Delivery::Send(recipient) => CosmosMsg::Bank(BankMsg::Send {
+            amount: vec![delivered],
+            to_address: recipient.to_string(),
+        }),
+        Delivery::Call { contract, msg } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: msg.clone(),
+            funds: vec![delivered],
+        }),
+    });
+    messages.extend(collect_fee(storage, &config, Coin { denom: registry.wrapped_denom.clone(), amount: fee })?);
+
+    Ok((messages, fee))
 }
 
-// Helper function to encode ERC20 transfer call
-fn encode_transfer_payload(recipient: [u8; 20], amount: Uint128) -> String {
-    let mut buff = Vec::with_capacity(68);
+pub fn handle_authorize_wrap_operator(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
 
-    buff.extend_from_slice(TRANSFER_SIG);
-    buff.extend_from_slice(&[0; 12]);
-    buff.extend_from_slice(&recipient);
-    buff.extend_from_slice(&[0; 16]);
-    buff.extend_from_slice(&amount.to_be_bytes());
+    let operator = deps.api.addr_validate(&operator)?;
+    WRAP_OPERATORS.save(deps.storage, (&info.sender, &operator), &Empty {})?;
 
-    Binary::from(buff).to_base64()
-}
\ No newline at end of file
+    Ok(Response::new()
+        .add_attribute("action", "authorize_wrap_operator")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn handle_revoke_wrap_operator(
+    deps: DepsMut<SeiQueryWrapper>,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    nonpayable(&info).map_err(TokenWrapperError::PaymentError)?;
+
+    let operator = deps.api.addr_validate(&operator)?;
+    WRAP_OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_wrap_operator")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator))
+}
+
+enum PauseAction {
+    Wrap,
+    Unwrap,
+}
+
+// Fails if the action is paused either globally or for the given wrapped denom
+fn assert_not_paused(storage: &dyn Storage, denom: &str, action: PauseAction) -> Result<(), TokenWrapperError> {
+    let global = CONFIG.load(storage)?.paused;
+    let token = TOKEN_INFO.may_load(storage, denom)?.map(|registry| registry.paused).unwrap_or_default();
+
+    match action {
+        PauseAction::Wrap if global.wrap || token.wrap => Err(TokenWrapperError::Paused("wrap".to_string())),
+        PauseAction::Unwrap if global.unwrap || token.unwrap => Err(TokenWrapperError::Paused("unwrap".to_string())),
+        _ => Ok(()),
+    }
+}
+
+// A reverted transfer reaches the reply only as a redacted codespace and code, so the causes
+// worth reporting are checked before the transfer is dispatched
+fn assert_erc20_allowance(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_addr: &str,
+    owner: [u8; 20],
+    spender: [u8; 20],
+    amount: Uint256,
+) -> Result<(), TokenWrapperError> {
+    let allowance = erc20_allowance(querier, env, token_addr, owner, spender)?;
+    if allowance < amount {
+        return Err(TokenWrapperError::InsufficientAllowance { token: token_addr.to_string(), amount, allowance });
+    }
+    Ok(())
+}
+
+fn assert_erc20_balance(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_addr: &str,
+    owner: [u8; 20],
+    amount: Uint256,
+) -> Result<(), TokenWrapperError> {
+    let balance = erc20_balance(querier, env, token_addr, owner)?;
+    if balance < amount {
+        return Err(TokenWrapperError::InsufficientBalance { token: token_addr.to_string(), amount, balance });
+    }
+    Ok(())
+}
+
+// Fails if the amount falls outside the configured per-operation limits
+fn assert_within_limits(limits: &AmountLimits, amount: Uint128) -> Result<(), TokenWrapperError> {
+    let below_min = limits.min_amount.map_or(false, |min| amount < min);
+    let above_max = limits.max_amount.map_or(false, |max| amount > max);
+    if below_min || above_max {
+        return Err(TokenWrapperError::AmountOutOfLimits(amount));
+    }
+    Ok(())
+}
+
+// Determines which EVM account an ERC20 wrap pulls tokens from. By default this is the
+// caller's own associated EVM address; pulling from any other account requires that
+// account's Sei owner to have authorized the caller as a wrap operator.
+fn resolve_evm_owner(
+    deps: Deps<SeiQueryWrapper>,
+    caller: &Addr,
+    evm_sender: Option<Binary>,
+) -> Result<[u8; 20], TokenWrapperError> {
+    let evm_sender = match evm_sender {
+        Some(sender) => sender,
+        None => return parse_evm_address(&associated_evm_address(&deps.querier, caller)?),
+    };
+
+    let owner: [u8; 20] = evm_sender
+        .as_slice()
+        .try_into()
+        .map_err(|_| TokenWrapperError::InvalidEvmAddress(format!("0x{}", hex::encode(&evm_sender))))?;
+    let owner_hex = format_evm_address(&owner);
+
+    let owner_sei = associated_sei_address(&deps.querier, &owner_hex)?
+        .ok_or_else(|| TokenWrapperError::UnauthorizedEvmSender(owner_hex.clone()))?;
+    let owner_sei = deps.api.addr_validate(&owner_sei)?;
+    if owner_sei != *caller && !WRAP_OPERATORS.has(deps.storage, (&owner_sei, caller)) {
+        return Err(TokenWrapperError::UnauthorizedEvmSender(owner_hex));
+    }
+
+    Ok(owner)
+}
+
+// Helper function to encode ERC20 transferFrom call
+fn encode_transfer_from_payload(
+    owner: [u8; 20],
+    recipient: [u8; 20],
+    amount: Uint256
+) -> String {
+    let args = [Token::Address(owner), Token::Address(recipient), Token::Uint(amount)];
+    Binary::from(abi::encode_call(TRANSFER_FROM_SIG, &args)).to_base64()
+}
+
+// Helper function to encode ERC20 transfer call
+fn encode_transfer_payload(recipient: [u8; 20], amount: Uint256) -> String {
+    let args = [Token::Address(recipient), Token::Uint(amount)];
+    Binary::from(abi::encode_call(TRANSFER_SIG, &args)).to_base64()
+}
@@ -0,0 +1,68 @@
+use cosmwasm_std::{BankMsg, Coin, CosmosMsg, StdError, Storage, Uint128};
+use sei_cosmwasm::SeiMsg;
+
+use crate::error::TokenWrapperError;
+use crate::msg::FeeSettings;
+use crate::state::{Config, TokenRegistry, ACCRUED_FEES};
+
+// Hard upper bound on any fee rate, 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+pub enum FeeAction {
+    Wrap,
+    Unwrap,
+}
+
+pub fn validate_fee_settings(fees: &FeeSettings) -> Result<(), TokenWrapperError> {
+    if fees.wrap_bps > MAX_FEE_BPS || fees.unwrap_bps > MAX_FEE_BPS {
+        return Err(TokenWrapperError::InvalidFee(format!("fee rates cannot exceed {MAX_FEE_BPS} bps")));
+    }
+    Ok(())
+}
+
+// Per-token settings take precedence over the contract defaults
+pub fn effective_fees<'a>(config: &'a Config, registry: &'a TokenRegistry) -> &'a FeeSettings {
+    registry.fees.as_ref().unwrap_or(&config.default_fees)
+}
+
+// Computes the fee charged on `amount`. The flat minimum only applies when the action
+// carries a non-zero rate, and no fee ever exceeds `MAX_FEE_BPS` of the amount, whatever the
+// decimals of the token the minimum is counted in.
+pub fn compute_fee(fees: &FeeSettings, action: FeeAction, amount: Uint128) -> Uint128 {
+    let bps = match action {
+        FeeAction::Wrap => fees.wrap_bps,
+        FeeAction::Unwrap => fees.unwrap_bps,
+    };
+    if bps == 0 {
+        return Uint128::zero();
+    }
+
+    let fee = amount.multiply_ratio(u128::from(bps), BPS_DENOMINATOR);
+    let max_fee = amount.multiply_ratio(u128::from(MAX_FEE_BPS), BPS_DENOMINATOR);
+    fee.max(fees.min_fee.unwrap_or_default()).min(max_fee)
+}
+
+// Routes a fee to the collector, or accrues it in the contract for later withdrawal
+pub fn collect_fee(
+    storage: &mut dyn Storage,
+    config: &Config,
+    fee: Coin,
+) -> Result<Option<CosmosMsg<SeiMsg>>, TokenWrapperError> {
+    if fee.amount.is_zero() {
+        return Ok(None);
+    }
+
+    match &config.fee_collector {
+        Some(collector) => Ok(Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: collector.to_string(),
+            amount: vec![fee],
+        }))),
+        None => {
+            ACCRUED_FEES.update(storage, &fee.denom, |accrued| -> Result<_, TokenWrapperError> {
+                Ok(accrued.unwrap_or_default().checked_add(fee.amount).map_err(StdError::from)?)
+            })?;
+            Ok(None)
+        },
+    }
+}
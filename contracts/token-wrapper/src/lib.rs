@@ -2,36 +2,86 @@ use cosmwasm_std::{
   to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, entry_point,
 };
 use sei_cosmwasm::{SeiMsg, SeiQueryWrapper};
+use crate::admin::validate_limits;
 use crate::error::TokenWrapperError;
-use crate::msg::{InstantiateMsg, MigrateMsg, TokenWrapperExecMsg, TokenWrapperQueryMsg};
-use crate::state::{CONFIG, Config, UNWRAP_EVM_CALL_ID, WRAP_EVM_CALL_ID};
+use crate::evm::lookup_contract_evm_address;
+use crate::fees::validate_fee_settings;
+use crate::msg::{InstantiateMsg, PauseFlags, MigrateMsg, TokenWrapperExecMsg, TokenWrapperQueryMsg};
+use crate::registry::register_token;
+use crate::state::{CONFIG, Config, PendingOp, PENDING_OPS};
 
 // Import internal modules
+mod abi;
+mod admin;
 mod error;
 pub mod msg;
 mod state;
 mod execute;
+mod evm;
+mod fees;
+mod migrations;
 mod query;
+mod registry;
+mod reply;
+
+#[cfg(test)]
+mod tests;
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[entry_point]
 pub fn instantiate(
-  deps: DepsMut<SeiQueryWrapper>,
-  _env: Env,
+  mut deps: DepsMut<SeiQueryWrapper>,
+  env: Env,
   info: MessageInfo,
-  _msg: InstantiateMsg,
+  msg: InstantiateMsg,
 ) -> Result<Response<SeiMsg>, TokenWrapperError> {
   cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  let default_fees = msg.default_fees.unwrap_or_default();
+  validate_fee_settings(&default_fees)?;
+  let limits = msg.limits.unwrap_or_default();
+  validate_limits(&limits)?;
   
-  // Set config with contract owner
+  // Set config with contract owner and the contract's EVM address, if already associated
+  let owner = match msg.owner {
+      Some(owner) => deps.api.addr_validate(&owner)?,
+      None => info.sender,
+  };
   let config = Config {
-      owner: Some(info.sender),
+      owner: Some(owner.clone()),
+      pending_owner: None,
+      pauser: msg.pauser.map(|pauser| deps.api.addr_validate(&pauser)).transpose()?,
+      paused: PauseFlags::default(),
+      registration_mode: msg.registration_mode.unwrap_or_default(),
+      default_fees,
+      fee_collector: msg.fee_collector.map(|collector| deps.api.addr_validate(&collector)).transpose()?,
+      limits,
+      contract_evm_address: lookup_contract_evm_address(&deps.querier, &env)?,
   };
   CONFIG.save(deps.storage, &config)?;
+
+  // Register the initial tokens the same way the owner would at runtime
+  let mut response = Response::new()
+      .add_attribute("method", "instantiate")
+      .add_attribute("owner", owner);
+  for token in msg.initial_tokens {
+      let (registry, messages) = register_token(
+          deps.branch(),
+          &env,
+          token.token_type,
+          &token.address,
+          token.subdenom,
+          token.wrapped_decimals,
+      )?;
+      response = response
+          .add_messages(messages)
+          .add_attribute("registered_token", registry.token_address)
+          .add_attribute("wrapped_denom", registry.wrapped_denom);
+  }
   
-  Ok(Response::new().add_attribute("method", "instantiate"))
+  Ok(response)
 }
 
 #[entry_point]
@@ -48,8 +98,56 @@ pub fn execute(
       TokenWrapperExecMsg::WrapERC20 { evm_sender, token_addr, amount, recipient } => {
           execute::handle_wrap_erc20(deps, env, info, evm_sender, token_addr, amount, recipient)
       },
-      TokenWrapperExecMsg::Unwrap { token_type, evm_recipient, cosmos_recipient } => {
-          execute::handle_unwrap(deps, env, info, token_type, evm_recipient, cosmos_recipient)
+      TokenWrapperExecMsg::Unwrap { token_type, evm_recipient, cosmos_recipient, recipients } => {
+          execute::handle_unwrap(deps, env, info, token_type, evm_recipient, cosmos_recipient, recipients)
+      },
+      TokenWrapperExecMsg::SetRegistrationMode { mode } => {
+          admin::handle_set_registration_mode(deps, info, mode)
+      },
+      TokenWrapperExecMsg::RegisterToken { token_type, address, subdenom, wrapped_decimals } => {
+          admin::handle_register_token(deps, env, info, token_type, address, subdenom, wrapped_decimals)
+      },
+      TokenWrapperExecMsg::UpdateDenomMetadata { denom, name, symbol, description } => {
+          admin::handle_update_denom_metadata(deps, env, info, denom, name, symbol, description)
+      },
+      TokenWrapperExecMsg::SetDefaultFees { fees } => {
+          admin::handle_set_default_fees(deps, info, fees)
+      },
+      TokenWrapperExecMsg::SetTokenFees { denom, fees } => {
+          admin::handle_set_token_fees(deps, info, denom, fees)
+      },
+      TokenWrapperExecMsg::SetFeeCollector { collector } => {
+          admin::handle_set_fee_collector(deps, info, collector)
+      },
+      TokenWrapperExecMsg::WithdrawFees { denoms, recipient } => {
+          admin::handle_withdraw_fees(deps, info, denoms, recipient)
+      },
+      TokenWrapperExecMsg::SetLimits { limits } => {
+          admin::handle_set_limits(deps, info, limits)
+      },
+      TokenWrapperExecMsg::SetFeeOnTransfer { denom, enabled } => {
+          admin::handle_set_fee_on_transfer(deps, info, denom, enabled)
+      },
+      TokenWrapperExecMsg::ProposeOwner { owner } => {
+          admin::handle_propose_owner(deps, info, owner)
+      },
+      TokenWrapperExecMsg::AcceptOwnership {} => {
+          admin::handle_accept_ownership(deps, info)
+      },
+      TokenWrapperExecMsg::RenounceOwnership {} => {
+          admin::handle_renounce_ownership(deps, info)
+      },
+      TokenWrapperExecMsg::SetPauser { pauser } => {
+          admin::handle_set_pauser(deps, info, pauser)
+      },
+      TokenWrapperExecMsg::SetPaused { denom, wrap, unwrap } => {
+          admin::handle_set_paused(deps, info, denom, wrap, unwrap)
+      },
+      TokenWrapperExecMsg::AuthorizeWrapOperator { operator } => {
+          execute::handle_authorize_wrap_operator(deps, info, operator)
+      },
+      TokenWrapperExecMsg::RevokeWrapOperator { operator } => {
+          execute::handle_revoke_wrap_operator(deps, info, operator)
       },
   }
 }
@@ -70,45 +168,58 @@ pub fn query(
       TokenWrapperQueryMsg::ListWrappedTokens { start_after, limit } => {
           to_json_binary(&query::list_wrapped_tokens(deps, start_after, limit)?)
       },
+      TokenWrapperQueryMsg::Config {} => {
+          to_json_binary(&query::config(deps)?)
+      },
+      TokenWrapperQueryMsg::PauseStatus { denom } => {
+          to_json_binary(&query::pause_status(deps, denom)?)
+      },
+      TokenWrapperQueryMsg::Fees { denom } => {
+          to_json_binary(&query::fees(deps, denom)?)
+      },
+      TokenWrapperQueryMsg::Solvency { denom } => {
+          to_json_binary(&query::solvency(deps, env, denom)?)
+      },
+      TokenWrapperQueryMsg::ContractEvmAddress {} => {
+          to_json_binary(&query::contract_evm_address(deps, env)?)
+      },
   }
 }
 
 #[entry_point]
 pub fn reply(
-  _deps: DepsMut<SeiQueryWrapper>,  // Fix: Add underscore prefix to unused variable
-  _env: Env,
+  deps: DepsMut<SeiQueryWrapper>,
+  env: Env,
   msg: Reply,
 ) -> Result<Response<SeiMsg>, TokenWrapperError> {
-  match msg.id {
-      WRAP_EVM_CALL_ID | UNWRAP_EVM_CALL_ID => {
-          const TRUE_BUT_IN_32_BYTES: [u8; 32] = [
-              0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8,
-              0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 1u8,
-          ];
-
-          let data = msg
-              .result
-              .into_result()
-              .map_err(|e| TokenWrapperError::FailedReply(cw_utils::ParseReplyError::SubMsgFailure(e)))?
-              .data
-              .ok_or(TokenWrapperError::FailedReply(cw_utils::ParseReplyError::ParseFailure("No return data".into())))?;
+  // Every EVM call is recorded under its own reply id, which is released once it replies
+  let op = PENDING_OPS
+      .may_load(deps.storage, msg.id)?
+      .ok_or(TokenWrapperError::InvalidReplyId(msg.id))?;
+  PENDING_OPS.remove(deps.storage, msg.id);
 
-          if data == TRUE_BUT_IN_32_BYTES {
-              Ok(Response::new())
-          } else {
-              Err(TokenWrapperError::UnexpectedEvmReply(data))
-          }
-      }
-      id => Err(TokenWrapperError::InvalidReplyId(id)),
+  match op {
+      PendingOp::Wrap(wrap) => reply::handle_wrap_reply(deps, env, msg.id, wrap, msg.result),
+      PendingOp::Unwrap(transfer) => {
+          reply::handle_transfer_reply(msg.id, "unwrap_erc20_confirmed", transfer, msg.result)
+      },
+      PendingOp::Refund(transfer) => {
+          reply::handle_transfer_reply(msg.id, "wrap_refund_confirmed", transfer, msg.result)
+      },
   }
 }
 
 #[entry_point]
 pub fn migrate(
-  deps: DepsMut<SeiQueryWrapper>,
-  _env: Env,
-  _msg: MigrateMsg,
+  mut deps: DepsMut<SeiQueryWrapper>,
+  env: Env,
+  msg: MigrateMsg,
 ) -> Result<Response<SeiMsg>, TokenWrapperError> {
+  let previous_version = migrations::migrate(deps.branch(), &env, msg, CONTRACT_NAME, CONTRACT_VERSION)?;
   cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-  Ok(Response::new().add_attribute("method", "migrate"))
-}
\ No newline at end of file
+
+  Ok(Response::new()
+      .add_attribute("method", "migrate")
+      .add_attribute("previous_version", previous_version.to_string())
+      .add_attribute("new_version", CONTRACT_VERSION))
+}
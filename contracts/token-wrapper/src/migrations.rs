@@ -0,0 +1,194 @@
+use cosmwasm_std::{DepsMut, Env, Order, StdResult};
+use sei_cosmwasm::SeiQueryWrapper;
+use semver::Version;
+
+use crate::admin::validate_limits;
+use crate::error::TokenWrapperError;
+use crate::evm::{canonical_erc20_address, lookup_contract_evm_address};
+use crate::fees::validate_fee_settings;
+use crate::msg::{MigrateMsg, TokenType};
+use crate::state::{CONFIG, CW20_TO_DENOM, ERC20_TO_DENOM, ESCROW, TOKEN_INFO, EscrowBalance, TokenRegistry};
+
+type MigrationStep = fn(DepsMut<SeiQueryWrapper>, &Env) -> Result<(), TokenWrapperError>;
+
+// Ordered migration steps, each tagged with the contract version that introduced it.
+// A step runs when upgrading from a version below its tag to one at or above it.
+const MIGRATIONS: &[(&str, MigrationStep)] = &[
+    ("0.2.0", rewrite_state_v0_2_0),
+    ("0.2.0", seed_escrow_v0_2_0),
+    ("0.2.0", canonicalize_erc20_addresses_v0_2_0),
+    ("0.2.0", reconcile_cw20_denoms_v0_2_0),
+];
+
+// Checks the stored contract version, runs every pending migration step in order and
+// applies the config parameters carried by the migrate message
+pub fn migrate(
+    mut deps: DepsMut<SeiQueryWrapper>,
+    env: &Env,
+    msg: MigrateMsg,
+    contract_name: &str,
+    contract_version: &str,
+) -> Result<Version, TokenWrapperError> {
+    let stored = cw2::get_contract_version(deps.storage)?;
+    if stored.contract != contract_name {
+        return Err(TokenWrapperError::InvalidContractName {
+            expected: contract_name.to_string(),
+            actual: stored.contract,
+        });
+    }
+
+    let stored_version: Version = stored.version.parse()?;
+    let new_version: Version = contract_version.parse()?;
+    if stored_version > new_version {
+        return Err(TokenWrapperError::CannotDowngrade {
+            stored: stored_version.to_string(),
+            new: new_version.to_string(),
+        });
+    }
+
+    for (step_version, step) in MIGRATIONS {
+        let step_version: Version = step_version.parse()?;
+        if stored_version < step_version && step_version <= new_version {
+            step(deps.branch(), env)?;
+        }
+    }
+
+    apply_migrate_msg(deps, env, msg)?;
+    Ok(stored_version)
+}
+
+fn apply_migrate_msg(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: &Env,
+    msg: MigrateMsg,
+) -> Result<(), TokenWrapperError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Once ownership is renounced an upgrade may no longer change the configuration
+    let changes_config = msg.registration_mode.is_some()
+        || msg.default_fees.is_some()
+        || msg.fee_collector.is_some()
+        || msg.pauser.is_some()
+        || msg.limits.is_some();
+    if changes_config && config.owner.is_none() {
+        return Err(TokenWrapperError::ContractImmutable);
+    }
+
+    // Refresh the cached EVM address in case the contract was associated since the last upgrade
+    if let Some(evm_addr) = lookup_contract_evm_address(&deps.querier, env)? {
+        config.contract_evm_address = Some(evm_addr);
+    }
+
+    if let Some(registration_mode) = msg.registration_mode {
+        config.registration_mode = registration_mode;
+    }
+    if let Some(default_fees) = msg.default_fees {
+        validate_fee_settings(&default_fees)?;
+        config.default_fees = default_fees;
+    }
+    if let Some(fee_collector) = msg.fee_collector {
+        config.fee_collector = Some(deps.api.addr_validate(&fee_collector)?);
+    }
+    if let Some(pauser) = msg.pauser {
+        config.pauser = Some(deps.api.addr_validate(&pauser)?);
+    }
+    if let Some(limits) = msg.limits {
+        validate_limits(&limits)?;
+        config.limits = limits;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    Ok(())
+}
+
+// 0.2.0 added ownership, pause, registration, fee and limit settings. Rewrite the config and
+// every registry entry so they are stored with all their fields filled in.
+fn rewrite_state_v0_2_0(deps: DepsMut<SeiQueryWrapper>, _env: &Env) -> Result<(), TokenWrapperError> {
+    let config = CONFIG.load(deps.storage)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let registries = TOKEN_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, TokenRegistry)>>>()?;
+    for (denom, registry) in registries {
+        TOKEN_INFO.save(deps.storage, &denom, &registry)?;
+    }
+
+    Ok(())
+}
+
+// 0.2.0 added the escrow ledger. Seed it from the live supply of each wrapped denom, which
+// every earlier version minted 1:1 against the underlying tokens it received.
+fn seed_escrow_v0_2_0(deps: DepsMut<SeiQueryWrapper>, _env: &Env) -> Result<(), TokenWrapperError> {
+    let denoms = TOKEN_INFO
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    for denom in denoms {
+        let supply = deps.querier.query_supply(&denom)?.amount;
+        ESCROW.save(deps.storage, &denom, &EscrowBalance {
+            total_locked: supply.into(),
+            total_minted: supply,
+        })?;
+    }
+
+    Ok(())
+}
+
+// Earlier versions keyed ERC20 tokens by the address exactly as given, so case variants of
+// one address were registered under separate keys pointing at the same denom. Merge them into
+// the canonical lowercase key and store the canonical address in the registry.
+fn canonicalize_erc20_addresses_v0_2_0(deps: DepsMut<SeiQueryWrapper>, _env: &Env) -> Result<(), TokenWrapperError> {
+    let entries = ERC20_TO_DENOM
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, String)>>>()?;
+    for (token_addr, denom) in entries {
+        let canonical = canonical_erc20_address(&token_addr)?;
+        if canonical == token_addr {
+            continue;
+        }
+        ERC20_TO_DENOM.remove(deps.storage, &token_addr);
+        ERC20_TO_DENOM.save(deps.storage, &canonical, &denom)?;
+    }
+
+    let registries = TOKEN_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, TokenRegistry)>>>()?;
+    for (denom, mut registry) in registries {
+        if registry.token_type == TokenType::ERC20 {
+            registry.token_address = canonical_erc20_address(&registry.token_address)?;
+            TOKEN_INFO.save(deps.storage, &denom, &registry)?;
+        }
+    }
+
+    Ok(())
+}
+
+// 0.2.0 derives CW20 subdenoms from a hash of the address. Registered tokens keep their
+// existing denom, so make sure every CW20 in the registry resolves to its own denom, and drop
+// reverse entries left behind when an earlier truncated subdenom collided and another token
+// overwrote the registry entry. Such a token registers afresh under a hashed denom.
+fn reconcile_cw20_denoms_v0_2_0(deps: DepsMut<SeiQueryWrapper>, _env: &Env) -> Result<(), TokenWrapperError> {
+    let entries = CW20_TO_DENOM
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (token_addr, denom) in entries {
+        let owned = TOKEN_INFO
+            .may_load(deps.storage, &denom)?
+            .is_some_and(|registry| registry.token_address == token_addr.as_str());
+        if !owned {
+            CW20_TO_DENOM.remove(deps.storage, &token_addr);
+        }
+    }
+
+    let registries = TOKEN_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, TokenRegistry)>>>()?;
+    for (denom, registry) in registries {
+        if registry.token_type == TokenType::CW20 {
+            let token_addr = deps.api.addr_validate(&registry.token_address)?;
+            CW20_TO_DENOM.save(deps.storage, &token_addr, &denom)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::{Addr, Binary, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128, Uint256};
 
 #[cosmwasm_schema::cw_serde]
 pub enum TokenType {
@@ -7,31 +7,244 @@ pub enum TokenType {
     CW20,
 }
 
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub enum RegistrationMode {
+    // Any valid token is registered the first time it is wrapped
+    #[default]
+    Permissionless,
+    // Only tokens registered by the owner can be wrapped
+    Allowlist,
+}
+
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    pub wrap: bool,
+    pub unwrap: bool,
+}
+
+// Fee rates in basis points of the wrapped amount, bounded by 1000 (10%)
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct FeeSettings {
+    pub wrap_bps: u16,
+    pub unwrap_bps: u16,
+    // Flat minimum charged whenever the rate for the operation is non-zero, itself capped at
+    // 10% of the amount
+    pub min_fee: Option<Uint128>,
+}
+
+// Bounds on the amount of a single wrap or unwrap, in base units of the wrapped token
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct AmountLimits {
+    pub min_amount: Option<Uint128>,
+    pub max_amount: Option<Uint128>,
+}
+
+// Set on tokens whose wrapped denom uses fewer decimals than the underlying token. One
+// wrapped base unit is backed by `factor` underlying base units.
+#[cosmwasm_schema::cw_serde]
+pub struct DecimalScaling {
+    pub wrapped_decimals: u8,
+    pub factor: Uint256,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct TokenInfo {
     pub token_type: TokenType,
     pub address: String,
     pub wrapped_denom: String,
+    pub fee_on_transfer: bool,
+    pub scaling: Option<DecimalScaling>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ConfigResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    pub pauser: Option<Addr>,
+    pub registration_mode: RegistrationMode,
+    pub limits: AmountLimits,
+    pub contract_evm_address: Option<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PauseStatusResponse {
+    pub global: PauseFlags,
+    // Only set when the status of a registered denom was requested
+    pub token: Option<PauseFlags>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct FeesResponse {
+    pub default_fees: FeeSettings,
+    pub fee_collector: Option<Addr>,
+    // Only set when the fees of a registered denom were requested
+    pub token_fees: Option<FeeSettings>,
+    pub accrued: Option<Uint128>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SolvencyResponse {
+    pub denom: String,
+    // Ledger of underlying tokens locked and wrapped tokens minted against them
+    pub total_locked: Uint256,
+    pub total_minted: Uint128,
+    // Live supply of the wrapped denom
+    pub bank_supply: Uint128,
+    // Underlying tokens actually held by the contract, which for ERC20s may exceed 128 bits
+    pub underlying_balance: Uint256,
+    // Whether the held balance covers the supply and the ledger covers what was minted
+    pub solvent: bool,
+}
+
+// Overrides the recipient of a single denom in an unwrap: an EVM recipient in any form
+// accepted by `evm_recipient` for ERC20-backed denoms, a Sei address for CW20-backed ones
+#[cosmwasm_schema::cw_serde]
+pub struct UnwrapRecipient {
+    pub denom: String,
+    pub recipient: String,
+}
+
+// Message attached to a CW20 `Send` to this contract. An empty message wraps to the sender.
+#[cosmwasm_schema::cw_serde]
+pub enum ReceiveMsg {
+    // Wraps to `recipient`, or to the CW20 sender when omitted
+    Wrap {
+        recipient: Option<String>,
+        memo: Option<String>,
+    },
+    // Wraps to the Sei account of an EVM address
+    WrapToEvm {
+        evm_address: String,
+        memo: Option<String>,
+    },
+    // Wraps and forwards the wrapped tokens to `contract` as funds of the `msg` execute message
+    WrapAndCall {
+        contract: String,
+        msg: Binary,
+        memo: Option<String>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
 pub enum TokenWrapperExecMsg {
-    // For CW20 tokens
+    // For CW20 tokens, with a `ReceiveMsg` attached
     Receive(cw20::Cw20ReceiveMsg),
     
-    // For ERC20 tokens
+    // For ERC20 tokens. Tokens are pulled from the caller's associated EVM address unless
+    // `evm_sender` names another account that has authorized the caller as a wrap operator.
     WrapERC20 {
-        evm_sender: Binary,
+        evm_sender: Option<Binary>,
         recipient: Option<Addr>,
         token_addr: String,
-        amount: Uint128,
+        // Full uint256 amount in the token's base units
+        amount: Uint256,
     },
     
-    // Unwrap operations
+    // Unwrap operations. Each sent denom is routed by the type of its underlying token; when
+    // given, `token_type` asserts that every sent denom is of that type.
     Unwrap {
-        token_type: TokenType,
-        evm_recipient: Option<Binary>,
+        token_type: Option<TokenType>,
+        // A `0x` address (EIP-55 checksummed if mixed case), a Sei address with an associated EVM
+        // address, or the base64 encoding of the raw 20 bytes
+        evm_recipient: Option<String>,
         cosmos_recipient: Option<Addr>,
+        #[serde(default)]
+        recipients: Vec<UnwrapRecipient>,
+    },
+
+    // Owner only: switches between permissionless and allowlist token registration
+    SetRegistrationMode {
+        mode: RegistrationMode,
+    },
+
+    // Owner only: registers a token and creates its wrapped denom ahead of the first wrap,
+    // optionally under a readable subdenom such as `wusdc` instead of the derived one, and with
+    // fewer decimals than the underlying token
+    RegisterToken {
+        token_type: TokenType,
+        address: String,
+        subdenom: Option<String>,
+        wrapped_decimals: Option<u8>,
+    },
+
+    // Owner only: republishes a wrapped denom's bank metadata from the underlying token,
+    // optionally overriding its name, symbol or description
+    UpdateDenomMetadata {
+        denom: String,
+        name: Option<String>,
+        symbol: Option<String>,
+        description: Option<String>,
+    },
+
+    // Owner only: sets the fees applied to tokens without their own settings
+    SetDefaultFees {
+        fees: FeeSettings,
+    },
+
+    // Owner only: sets or clears the fee settings of a single wrapped denom
+    SetTokenFees {
+        denom: String,
+        fees: Option<FeeSettings>,
+    },
+
+    // Owner only: sets or clears the account fees are sent to as they are charged
+    SetFeeCollector {
+        collector: Option<String>,
+    },
+
+    // Owner only: withdraws the fees accrued while no collector was set
+    WithdrawFees {
+        denoms: Vec<String>,
+        recipient: Option<String>,
+    },
+
+    // Owner only: sets the bounds on the amount of a single wrap or unwrap
+    SetLimits {
+        limits: AmountLimits,
+    },
+
+    // Owner only: switches an ERC20's wrapped amount to the balance the contract actually receives
+    SetFeeOnTransfer {
+        denom: String,
+        enabled: bool,
+    },
+
+    // Owner only: proposes a new owner, who must accept before the transfer takes effect
+    ProposeOwner {
+        owner: String,
+    },
+
+    // Accepts a pending ownership transfer
+    AcceptOwnership {},
+
+    // Owner only: permanently gives up ownership, making the configuration immutable
+    RenounceOwnership {},
+
+    // Owner only: sets or clears the account allowed to pause and unpause
+    SetPauser {
+        pauser: Option<String>,
+    },
+
+    // Owner or pauser: pauses or resumes wrapping and unwrapping, for a single wrapped denom
+    // or globally when `denom` is omitted. Flags left unset keep their current value.
+    SetPaused {
+        denom: Option<String>,
+        wrap: Option<bool>,
+        unwrap: Option<bool>,
+    },
+
+    // Allows `operator` to wrap ERC20 tokens out of the caller's associated EVM account
+    AuthorizeWrapOperator {
+        operator: String,
+    },
+
+    // Removes a previously authorized wrap operator
+    RevokeWrapOperator {
+        operator: String,
     },
 }
 
@@ -52,12 +265,56 @@ pub enum TokenWrapperQueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+
+    #[returns(ConfigResponse)]
+    Config {},
+
+    #[returns(PauseStatusResponse)]
+    PauseStatus { denom: Option<String> },
+
+    #[returns(FeesResponse)]
+    Fees { denom: Option<String> },
+
+    #[returns(SolvencyResponse)]
+    Solvency { denom: String },
+
+    // EVM address of this contract, which ERC20 holders must `approve` before wrapping
+    #[returns(String)]
+    ContractEvmAddress {},
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct InitialToken {
+    pub token_type: TokenType,
+    pub address: String,
+    pub subdenom: Option<String>,
+    pub wrapped_decimals: Option<u8>,
 }
 
-// Used for contract instantiation
+// Used for contract instantiation. Everything is optional: the owner defaults to the
+// instantiator and the remaining settings to their runtime defaults.
 #[cosmwasm_schema::cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub owner: Option<String>,
+    pub pauser: Option<String>,
+    pub registration_mode: Option<RegistrationMode>,
+    pub default_fees: Option<FeeSettings>,
+    pub fee_collector: Option<String>,
+    pub limits: Option<AmountLimits>,
+    // Tokens registered, with their wrapped denoms created, as part of instantiation
+    #[serde(default)]
+    pub initial_tokens: Vec<InitialToken>,
+}
 
-// Used for contract migration
+// Used for contract migration. Every field is optional and, when set, overrides the
+// corresponding config value after the migration steps have run. Config values can only be
+// changed while the contract still has an owner.
 #[cosmwasm_schema::cw_serde]
-pub struct MigrateMsg {}
\ No newline at end of file
+#[derive(Default)]
+pub struct MigrateMsg {
+    pub registration_mode: Option<RegistrationMode>,
+    pub default_fees: Option<FeeSettings>,
+    pub fee_collector: Option<String>,
+    pub pauser: Option<String>,
+    pub limits: Option<AmountLimits>,
+}
\ No newline at end of file
@@ -1,9 +1,10 @@
-use cosmwasm_std::{Deps, Env, StdError, StdResult, Order};
+use cosmwasm_std::{Deps, Env, StdError, StdResult, Order, Uint128, Uint256};
 use sei_cosmwasm::SeiQueryWrapper;
 use cw_storage_plus::Bound;
 
-use crate::msg::{TokenInfo, TokenType};
-use crate::state::{TOKEN_INFO, ERC20_TO_DENOM, CW20_TO_DENOM, token_addr_to_subdenom};
+use crate::evm::{canonical_erc20_address, erc20_balance, lookup_contract_evm_address, parse_evm_address};
+use crate::msg::{ConfigResponse, FeesResponse, PauseStatusResponse, SolvencyResponse, TokenInfo, TokenType};
+use crate::state::{ACCRUED_FEES, CONFIG, ESCROW, TOKEN_INFO, ERC20_TO_DENOM, CW20_TO_DENOM, token_addr_to_subdenom};
 
 // Default pagination limit
 const DEFAULT_LIMIT: u32 = 10;
@@ -20,6 +21,8 @@ pub fn token_info(
         token_type: registry.token_type,
         address: registry.token_address,
         wrapped_denom: registry.wrapped_denom,
+        fee_on_transfer: registry.fee_on_transfer,
+        scaling: registry.scaling,
     })
 }
 
@@ -31,6 +34,8 @@ pub fn wrapped_denom(
 ) -> StdResult<String> {
     match token_type {
         TokenType::ERC20 => {
+            let token_address = canonical_erc20_address(&token_address)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
             if let Some(denom) = ERC20_TO_DENOM.may_load(deps.storage, &token_address)? {
                 return Ok(denom);
             }
@@ -44,7 +49,7 @@ pub fn wrapped_denom(
     }
     
     // If not found, compute what it would be
-    let subdenom = token_addr_to_subdenom(&token_type, &token_address);
+    let subdenom = token_addr_to_subdenom(deps.api, &token_type, &token_address)?;
     let denom = format!("factory/{}/{}", env.contract.address, subdenom);
     Ok(denom)
 }
@@ -67,9 +72,126 @@ pub fn list_wrapped_tokens(
                 token_type: registry.token_type,
                 address: registry.token_address,
                 wrapped_denom: registry.wrapped_denom,
+                fee_on_transfer: registry.fee_on_transfer,
+                scaling: registry.scaling,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
     
     Ok(tokens)
+}
+
+pub fn config(
+    deps: Deps<SeiQueryWrapper>,
+) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: config.owner,
+        pending_owner: config.pending_owner,
+        pauser: config.pauser,
+        registration_mode: config.registration_mode,
+        limits: config.limits,
+        contract_evm_address: config.contract_evm_address,
+    })
+}
+
+pub fn pause_status(
+    deps: Deps<SeiQueryWrapper>,
+    denom: Option<String>,
+) -> StdResult<PauseStatusResponse> {
+    let token = match denom {
+        Some(denom) => {
+            let registry = TOKEN_INFO.load(deps.storage, &denom)
+                .map_err(|_| StdError::generic_err("Token not found"))?;
+            Some(registry.paused)
+        },
+        None => None,
+    };
+
+    Ok(PauseStatusResponse {
+        global: CONFIG.load(deps.storage)?.paused,
+        token,
+    })
+}
+
+pub fn fees(
+    deps: Deps<SeiQueryWrapper>,
+    denom: Option<String>,
+) -> StdResult<FeesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (token_fees, accrued) = match denom {
+        Some(denom) => {
+            let registry = TOKEN_INFO.load(deps.storage, &denom)
+                .map_err(|_| StdError::generic_err("Token not found"))?;
+            let accrued = ACCRUED_FEES.may_load(deps.storage, &denom)?.unwrap_or_default();
+            (registry.fees, Some(accrued))
+        },
+        None => (None, None),
+    };
+
+    Ok(FeesResponse {
+        default_fees: config.default_fees,
+        fee_collector: config.fee_collector,
+        token_fees,
+        accrued,
+    })
+}
+
+pub fn solvency(
+    deps: Deps<SeiQueryWrapper>,
+    env: Env,
+    denom: String,
+) -> StdResult<SolvencyResponse> {
+    let registry = TOKEN_INFO.load(deps.storage, &denom)
+        .map_err(|_| StdError::generic_err("Token not found"))?;
+    let escrow = ESCROW.may_load(deps.storage, &denom)?.unwrap_or_default();
+    let bank_supply = deps.querier.query_supply(&denom)?.amount;
+    let underlying_balance = underlying_balance(deps, &env, &registry.token_type, &registry.token_address)?;
+    // Underlying amount needed to back a wrapped amount
+    let backing = |wrapped: Uint128| Uint256::from(wrapped).checked_mul(registry.scale_factor());
+
+    Ok(SolvencyResponse {
+        denom,
+        total_locked: escrow.total_locked,
+        total_minted: escrow.total_minted,
+        bank_supply,
+        underlying_balance,
+        solvent: underlying_balance >= backing(bank_supply)? && escrow.total_locked >= backing(escrow.total_minted)?,
+    })
+}
+
+// Balance of the underlying token held by this contract
+pub fn underlying_balance(
+    deps: Deps<SeiQueryWrapper>,
+    env: &Env,
+    token_type: &TokenType,
+    token_address: &str,
+) -> StdResult<Uint256> {
+    match token_type {
+        TokenType::CW20 => {
+            let balance = deps.querier.query_wasm_smart::<cw20::BalanceResponse>(
+                token_address,
+                &cw20::Cw20QueryMsg::Balance { address: env.contract.address.to_string() },
+            )?;
+            Ok(balance.balance.into())
+        },
+        TokenType::ERC20 => {
+            let balance = parse_evm_address(&contract_evm_address(deps, env.clone())?)
+                .and_then(|contract_addr| erc20_balance(&deps.querier, env, token_address, contract_addr));
+            balance.map_err(|e| StdError::generic_err(e.to_string()))
+        },
+    }
+}
+
+pub fn contract_evm_address(
+    deps: Deps<SeiQueryWrapper>,
+    env: Env,
+) -> StdResult<String> {
+    if let Some(evm_addr) = CONFIG.load(deps.storage)?.contract_evm_address {
+        return Ok(evm_addr);
+    }
+
+    lookup_contract_evm_address(&deps.querier, &env)?
+        .ok_or_else(|| StdError::generic_err("Contract has no associated EVM address"))
 }
\ No newline at end of file
@@ -0,0 +1,234 @@
+use cosmwasm_std::{Addr, Api, DepsMut, Env, QuerierWrapper, StdError, Storage, Uint128, Uint256};
+use cw20::TokenInfoResponse;
+use sei_cosmwasm::{DenomUnit, Metadata, SeiMsg, SeiQuerier, SeiQueryWrapper};
+
+use crate::error::TokenWrapperError;
+use crate::evm::{canonical_erc20_address, parse_evm_address, to_native_amount};
+use crate::msg::{DecimalScaling, PauseFlags, RegistrationMode, TokenType};
+use crate::state::{CONFIG, CW20_TO_DENOM, ERC20_TO_DENOM, TOKEN_INFO, token_addr_to_subdenom, TokenRegistry};
+
+// Longest subdenom tokenfactory accepts
+const MAX_SUBDENOM_LENGTH: usize = 44;
+// Prefixes of subdenoms derived from token addresses
+const RESERVED_SUBDENOM_PREFIXES: [&str; 2] = ["crwn", "cw20"];
+
+// Normalizes a token address to the form the registry is keyed by
+fn canonical_token_address(api: &dyn Api, token_type: &TokenType, token_addr: &str) -> Result<String, TokenWrapperError> {
+    match token_type {
+        TokenType::ERC20 => canonical_erc20_address(token_addr),
+        TokenType::CW20 => Ok(api.addr_validate(token_addr)?.to_string()),
+    }
+}
+
+// Looks up the registry entry of an already registered token by its canonical address
+pub fn load_registered(
+    storage: &dyn Storage,
+    token_type: &TokenType,
+    token_addr: &str,
+) -> Result<Option<TokenRegistry>, TokenWrapperError> {
+    let denom = match token_type {
+        TokenType::ERC20 => ERC20_TO_DENOM.may_load(storage, token_addr)?,
+        TokenType::CW20 => CW20_TO_DENOM.may_load(storage, &Addr::unchecked(token_addr))?,
+    };
+    match denom {
+        Some(denom) => Ok(Some(TOKEN_INFO.load(storage, &denom)?)),
+        None => Ok(None),
+    }
+}
+
+// Returns the registry entry of a token being wrapped, registering it on the fly when the
+// contract is in permissionless mode. Any messages needed to set up the wrapped denom are
+// returned alongside it.
+pub fn load_or_register(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: &Env,
+    token_type: TokenType,
+    token_addr: &str,
+) -> Result<(TokenRegistry, Vec<SeiMsg>), TokenWrapperError> {
+    let token_addr = &canonical_token_address(deps.api, &token_type, token_addr)?;
+    if let Some(registry) = load_registered(deps.storage, &token_type, token_addr)? {
+        return Ok((registry, vec![]));
+    }
+
+    if CONFIG.load(deps.storage)?.registration_mode == RegistrationMode::Allowlist {
+        return Err(TokenWrapperError::TokenNotRegistered);
+    }
+
+    register_token(deps, env, token_type, token_addr, None, None)
+}
+
+// Validates a token, records it in the registry and returns the messages creating its
+// wrapped denom and publishing its metadata. The subdenom is derived from the token address
+// unless an alias is given, and the wrapped denom keeps the token's decimals unless fewer
+// are requested.
+pub fn register_token(
+    deps: DepsMut<SeiQueryWrapper>,
+    env: &Env,
+    token_type: TokenType,
+    token_addr: &str,
+    alias: Option<String>,
+    wrapped_decimals: Option<u8>,
+) -> Result<(TokenRegistry, Vec<SeiMsg>), TokenWrapperError> {
+    let token_addr = &canonical_token_address(deps.api, &token_type, token_addr)?;
+    if load_registered(deps.storage, &token_type, token_addr)?.is_some() {
+        return Err(TokenWrapperError::TokenAlreadyRegistered(token_addr.to_string()));
+    }
+
+    let subdenom = match alias {
+        Some(alias) => {
+            validate_subdenom_alias(&alias)?;
+            alias
+        },
+        None => token_addr_to_subdenom(deps.api, &token_type, token_addr)?,
+    };
+    let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+    // Never let a second token take over an existing wrapped denom
+    if TOKEN_INFO.has(deps.storage, &denom) {
+        return Err(TokenWrapperError::DenomCollision(denom));
+    }
+
+    let token_info = query_token_info(&deps.querier, env, &token_type, token_addr)?;
+    let scaling = decimal_scaling(token_info.decimals, wrapped_decimals)?;
+    match token_type {
+        TokenType::ERC20 => ERC20_TO_DENOM.save(deps.storage, token_addr, &denom)?,
+        TokenType::CW20 => CW20_TO_DENOM.save(deps.storage, &deps.api.addr_validate(token_addr)?, &denom)?,
+    }
+
+    let registry = TokenRegistry {
+        token_type,
+        token_address: token_addr.to_string(),
+        wrapped_denom: denom.clone(),
+        fee_on_transfer: false,
+        paused: PauseFlags::default(),
+        fees: None,
+        scaling,
+    };
+    TOKEN_INFO.save(deps.storage, &denom, &registry)?;
+
+    // The bank module rejects metadata without a name or symbol, which must not block the
+    // registration itself
+    let mut msgs = vec![SeiMsg::CreateDenom { subdenom }];
+    msgs.extend(denom_metadata(&registry, &token_info).map(|metadata| SeiMsg::SetMetadata { metadata }));
+    Ok((registry, msgs))
+}
+
+// Aliases follow the tokenfactory subdenom rules and may not use the prefixes of derived
+// subdenoms, so they can never take the denom of a token registered later
+fn validate_subdenom_alias(alias: &str) -> Result<(), TokenWrapperError> {
+    let valid = !alias.is_empty()
+        && alias.len() <= MAX_SUBDENOM_LENGTH
+        && alias.starts_with(|c: char| c.is_ascii_alphabetic())
+        && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '/')
+        && !RESERVED_SUBDENOM_PREFIXES.iter().any(|prefix| alias.starts_with(prefix));
+    if !valid {
+        return Err(TokenWrapperError::InvalidSubdenom(alias.to_string()));
+    }
+    Ok(())
+}
+
+// Wrapped denoms can only use fewer decimals than the underlying token, so that every wrapped
+// base unit is backed by a whole number of underlying base units
+pub fn decimal_scaling(decimals: u8, wrapped_decimals: Option<u8>) -> Result<Option<DecimalScaling>, TokenWrapperError> {
+    match wrapped_decimals {
+        Some(wrapped_decimals) if wrapped_decimals > decimals => {
+            Err(TokenWrapperError::InvalidDecimals { decimals, wrapped_decimals })
+        },
+        Some(wrapped_decimals) if wrapped_decimals < decimals => {
+            let factor = Uint256::from(10u128)
+                .checked_pow(u32::from(decimals - wrapped_decimals))
+                .map_err(|_| TokenWrapperError::InvalidDecimals { decimals, wrapped_decimals })?;
+            Ok(Some(DecimalScaling { wrapped_decimals, factor }))
+        },
+        _ => Ok(None),
+    }
+}
+
+// Converts an underlying amount to the wrapped amount it backs, rejecting amounts that leave
+// dust the wrapped denom cannot represent
+pub fn to_wrapped_amount(registry: &TokenRegistry, amount: Uint256) -> Result<Uint128, TokenWrapperError> {
+    let (wrapped, dust) = split_wrapped_amount(registry, amount)?;
+    if !dust.is_zero() {
+        return Err(TokenWrapperError::AmountNotScalable { amount, factor: registry.scale_factor() });
+    }
+    Ok(wrapped)
+}
+
+// Splits an underlying amount into the wrapped amount it backs and the dust left over
+pub fn split_wrapped_amount(registry: &TokenRegistry, amount: Uint256) -> Result<(Uint128, Uint256), TokenWrapperError> {
+    let factor = registry.scale_factor();
+    Ok((to_native_amount(amount / factor)?, amount % factor))
+}
+
+// Converts a wrapped amount back to the underlying amount backing it
+pub fn to_underlying_amount(registry: &TokenRegistry, amount: Uint128) -> Result<Uint256, TokenWrapperError> {
+    Ok(Uint256::from(amount).checked_mul(registry.scale_factor()).map_err(StdError::from)?)
+}
+
+// Queries name, symbol and decimals of the underlying token, which also validates that the
+// address really is a token of the given type
+pub fn query_token_info(
+    querier: &QuerierWrapper<SeiQueryWrapper>,
+    env: &Env,
+    token_type: &TokenType,
+    token_addr: &str,
+) -> Result<TokenInfoResponse, TokenWrapperError> {
+    match token_type {
+        TokenType::ERC20 => {
+            parse_evm_address(token_addr)?;
+            SeiQuerier::new(querier)
+                .erc20_token_info(token_addr.to_string(), env.contract.address.to_string())
+                .map_err(TokenWrapperError::InvalidERC20Contract)
+        },
+        TokenType::CW20 => Ok(querier.query_wasm_smart(token_addr, &cw20::Cw20QueryMsg::TokenInfo {})?),
+    }
+}
+
+// Builds bank metadata for a wrapped denom, displaying it with the wrapped decimals. Returns
+// `None` when the token has no name or symbol, which the bank module requires.
+pub fn denom_metadata(registry: &TokenRegistry, token_info: &TokenInfoResponse) -> Option<Metadata> {
+    if token_info.name.trim().is_empty() || token_info.symbol.trim().is_empty() {
+        return None;
+    }
+    let base = registry.wrapped_denom.clone();
+    let decimals = registry.scaling.as_ref().map_or(token_info.decimals, |scaling| scaling.wrapped_decimals);
+
+    let mut denom_units = vec![DenomUnit {
+        denom: base.clone(),
+        exponent: 0,
+        aliases: vec![],
+    }];
+    // A token without decimals, or whose symbol does not make a valid denom, is displayed in
+    // base units
+    let display = match display_denom(&token_info.symbol) {
+        Some(display) if decimals > 0 => {
+            denom_units.push(DenomUnit {
+                denom: display.clone(),
+                exponent: decimals.into(),
+                aliases: vec![],
+            });
+            display
+        },
+        _ => base.clone(),
+    };
+
+    Some(Metadata {
+        description: format!("Wrapped {:?} token {}", registry.token_type, registry.token_address),
+        denom_units,
+        base,
+        display,
+        name: token_info.name.clone(),
+        symbol: token_info.symbol.clone(),
+    })
+}
+
+// Derives the display denom from a token symbol, dropping the characters bank denoms may not
+// contain. Denoms start with a letter and are 3 to 128 characters long.
+fn display_denom(symbol: &str) -> Option<String> {
+    let display: String = symbol
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || "/:._-".contains(*c))
+        .collect();
+    let valid = (3..=128).contains(&display.len()) && display.starts_with(|c: char| c.is_ascii_alphabetic());
+    valid.then_some(display)
+}
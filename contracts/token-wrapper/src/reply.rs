@@ -0,0 +1,107 @@
+use cosmwasm_std::{DepsMut, Env, Event, Response, SubMsgResult};
+use sei_cosmwasm::{SeiMsg, SeiQueryWrapper};
+
+use crate::abi::{self, ParamType, Token};
+use crate::error::TokenWrapperError;
+use crate::evm::{contract_evm_address, erc20_balance};
+use crate::execute::{erc20_transfer, mint_wrapped, Delivery};
+use crate::registry::split_wrapped_amount;
+use crate::state::{TOKEN_INFO, record_lock, PendingOp, PendingTransfer, PendingWrap};
+
+pub fn handle_wrap_reply(
+    mut deps: DepsMut<SeiQueryWrapper>,
+    env: Env,
+    id: u64,
+    pending: PendingWrap,
+    result: SubMsgResult,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    // Only mint once the tokens have provably reached the contract
+    validate_transfer_result(result)?;
+
+    // For fee-on-transfer tokens, only what actually arrived backs the wrapped tokens
+    let received = match pending.balance_before {
+        Some(balance_before) => {
+            let contract_addr = contract_evm_address(deps.branch(), &env)?;
+            let balance_after = erc20_balance(&deps.querier, &env, &pending.token_address, contract_addr)?;
+            balance_after.saturating_sub(balance_before).min(pending.amount)
+        },
+        None => pending.amount,
+    };
+
+    // Whatever the wrapped denom cannot represent goes back to the EVM owner. Only the measured
+    // amount of fee-on-transfer tokens can leave dust, requested amounts are checked up front.
+    let registry = TOKEN_INFO.load(deps.storage, &pending.wrapped_denom)?;
+    let (amount, dust) = split_wrapped_amount(&registry, received)?;
+    if amount.is_zero() {
+        return Err(TokenWrapperError::NoTokensReceived);
+    }
+    let mut response = Response::new();
+    if !dust.is_zero() {
+        let refund = PendingTransfer {
+            token_address: pending.token_address.clone(),
+            wrapped_denom: pending.wrapped_denom.clone(),
+            amount: dust,
+            recipient: pending.evm_owner.clone(),
+        };
+        response = response.add_submessage(erc20_transfer(deps.storage, refund, PendingOp::Refund)?);
+    }
+
+    // Mint the wrapped tokens and deliver them minus the wrap fee
+    record_lock(deps.storage, &registry.wrapped_denom, received - dust, amount)?;
+    let (messages, fee) = mint_wrapped(deps.storage, &registry, amount, &Delivery::Send(pending.recipient.clone()))?;
+
+    Ok(response
+        .add_messages(messages)
+        .add_event(
+            Event::new("wrap_erc20_confirmed")
+                .add_attribute("reply_id", id.to_string())
+                .add_attribute("token", pending.token_address)
+                .add_attribute("wrapped_denom", pending.wrapped_denom)
+                .add_attribute("requested_amount", pending.amount.to_string())
+                .add_attribute("received_amount", received.to_string())
+                .add_attribute("refunded_amount", dust.to_string())
+                .add_attribute("amount", amount.to_string())
+                .add_attribute("fee", fee.to_string())
+                .add_attribute("recipient", pending.recipient),
+        ))
+}
+
+// Confirms an outgoing ERC20 transfer and reports which denom, amount and recipient it was for
+pub fn handle_transfer_reply(
+    id: u64,
+    event: &str,
+    transfer: PendingTransfer,
+    result: SubMsgResult,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    validate_transfer_result(result)?;
+
+    Ok(Response::new().add_event(
+        Event::new(event)
+            .add_attribute("reply_id", id.to_string())
+            .add_attribute("token", transfer.token_address)
+            .add_attribute("wrapped_denom", transfer.wrapped_denom)
+            .add_attribute("amount", transfer.amount.to_string())
+            .add_attribute("recipient", transfer.recipient),
+    ))
+}
+
+// Checks that an ERC20 `transfer`/`transferFrom` call succeeded. Follows SafeERC20 semantics:
+// tokens that return nothing (e.g. USDT) are treated as successful, tokens that return data
+// must return exactly an ABI-encoded `true`. A revert cannot be told apart from any other
+// failure: wasmd redacts submessage errors to their codespace and code before the reply sees
+// them, which is why allowances and balances are checked before dispatching the transfer.
+fn validate_transfer_result(result: SubMsgResult) -> Result<(), TokenWrapperError> {
+    let data = result
+        .into_result()
+        .map_err(|e| TokenWrapperError::FailedReply(cw_utils::ParseReplyError::SubMsgFailure(e)))?
+        .data
+        .unwrap_or_default();
+
+    let returned_true = data.len() == 32
+        && abi::decode(&[ParamType::Bool], &data).is_ok_and(|tokens| tokens == [Token::Bool(true)]);
+    if data.is_empty() || returned_true {
+        Ok(())
+    } else {
+        Err(TokenWrapperError::UnexpectedEvmReply(data))
+    }
+}
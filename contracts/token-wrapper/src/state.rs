@@ -1,19 +1,99 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Api, Empty, StdResult, Storage, Uint128, Uint256};
 use cosmwasm_schema::cw_serde;
 use cw_storage_plus::{Item, Map};
+use sha2::{Digest, Sha256};
 
-use crate::msg::TokenType;
+use crate::msg::{AmountLimits, DecimalScaling, FeeSettings, PauseFlags, RegistrationMode, TokenType};
 
 #[cw_serde]
 pub struct TokenRegistry {
     pub token_type: TokenType,
     pub token_address: String,  // ERC20 address or CW20 address
     pub wrapped_denom: String,
+    // Mint the measured balance increase rather than the requested amount (ERC20 only)
+    #[serde(default)]
+    pub fee_on_transfer: bool,
+    // Per-token pause switches, checked in addition to the global ones
+    #[serde(default)]
+    pub paused: PauseFlags,
+    // Overrides the default fee settings for this token
+    #[serde(default)]
+    pub fees: Option<FeeSettings>,
+    // Scales amounts between the underlying token and a wrapped denom with fewer decimals
+    #[serde(default)]
+    pub scaling: Option<DecimalScaling>,
+}
+
+impl TokenRegistry {
+    // Underlying base units backing one wrapped base unit
+    pub fn scale_factor(&self) -> Uint256 {
+        self.scaling.as_ref().map_or(Uint256::one(), |scaling| scaling.factor)
+    }
+}
+
+// Underlying tokens held against a wrapped denom and the wrapped supply minted against them
+#[cw_serde]
+#[derive(Default)]
+pub struct EscrowBalance {
+    pub total_locked: Uint256,
+    pub total_minted: Uint128,
+}
+
+// EVM call awaiting its reply
+#[cw_serde]
+pub enum PendingOp {
+    // ERC20 wrap awaiting confirmation of its `transferFrom` call
+    Wrap(PendingWrap),
+    // ERC20 `transfer` returning the underlying tokens of an unwrap
+    Unwrap(PendingTransfer),
+    // ERC20 `transfer` returning dust a fee-on-transfer wrap could not wrap
+    Refund(PendingTransfer),
+}
+
+#[cw_serde]
+pub struct PendingWrap {
+    pub token_address: String,
+    pub wrapped_denom: String,
+    pub amount: Uint256,
+    pub recipient: Addr,
+    // Contract's token balance before the transfer, set for fee-on-transfer tokens
+    pub balance_before: Option<Uint256>,
+    // EVM account the tokens are pulled from, refunded any amount too small to wrap
+    pub evm_owner: String,
+}
+
+#[cw_serde]
+pub struct PendingTransfer {
+    pub token_address: String,
+    pub wrapped_denom: String,
+    pub amount: Uint256,
+    pub recipient: String,
 }
 
 #[cw_serde]
 pub struct Config {
+    // `None` once ownership has been renounced, which makes the configuration immutable
     pub owner: Option<Addr>,
+    // Proposed owner that has yet to accept the transfer
+    pub pending_owner: Option<Addr>,
+    // Account allowed to pause and unpause besides the owner
+    pub pauser: Option<Addr>,
+    // Global pause switches
+    #[serde(default)]
+    pub paused: PauseFlags,
+    // Whether unknown tokens are registered on their first wrap or must be registered by the owner
+    #[serde(default)]
+    pub registration_mode: RegistrationMode,
+    // Fee settings applied to tokens without their own
+    #[serde(default)]
+    pub default_fees: FeeSettings,
+    // Receives fees as they are charged; when unset they accrue in the contract
+    pub fee_collector: Option<Addr>,
+    // Bounds on the amount of a single wrap or unwrap
+    #[serde(default)]
+    pub limits: AmountLimits,
+    // EVM address associated with this contract, used as the `transferFrom` recipient
+    pub contract_evm_address: Option<String>,
 }
 
 // Primary state - config, token registries
@@ -28,29 +108,76 @@ pub const ERC20_TO_DENOM: Map<&str, String> = Map::new("erc20_to_denom");
 // Maps token address to wrapped denom for CW20 tokens
 pub const CW20_TO_DENOM: Map<&Addr, String> = Map::new("cw20_to_denom");
 
+// Escrow ledger, keyed by wrapped denom
+pub const ESCROW: Map<&str, EscrowBalance> = Map::new("escrow");
+
+// Fees accrued in the contract while no fee collector is set, keyed by wrapped denom
+pub const ACCRUED_FEES: Map<&str, Uint128> = Map::new("accrued_fees");
+
+// Operators allowed to wrap ERC20 tokens out of an owner's associated EVM account,
+// keyed by (owner, operator)
+pub const WRAP_OPERATORS: Map<(&Addr, &Addr), Empty> = Map::new("wrap_operators");
+
+// In-flight EVM calls, keyed by the reply id of their submessage
+pub const PENDING_OPS: Map<u64, PendingOp> = Map::new("pending_ops");
+
+// Next reply id to hand out for a submessage
+pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
+
 // Constants
 pub const BASE32_ALGORITHM: base32::Alphabet = base32::Alphabet::Rfc4648Lower { padding: false };
-pub const WRAP_EVM_CALL_ID: u64 = 2571182633660066190;
-pub const UNWRAP_EVM_CALL_ID: u64 = 13078395618759265986;
 
 // Utils
-pub fn token_addr_to_subdenom(token_type: &TokenType, token_addr: &str) -> String {
+pub fn record_lock(storage: &mut dyn Storage, denom: &str, locked: Uint256, minted: Uint128) -> StdResult<()> {
+    ESCROW.update(storage, denom, |escrow| -> StdResult<_> {
+        let escrow = escrow.unwrap_or_default();
+        Ok(EscrowBalance {
+            total_locked: escrow.total_locked.checked_add(locked)?,
+            total_minted: escrow.total_minted.checked_add(minted)?,
+        })
+    })?;
+    Ok(())
+}
+
+pub fn record_release(storage: &mut dyn Storage, denom: &str, released: Uint256, burned: Uint128) -> StdResult<()> {
+    ESCROW.update(storage, denom, |escrow| -> StdResult<_> {
+        let escrow = escrow.unwrap_or_default();
+        Ok(EscrowBalance {
+            total_locked: escrow.total_locked.checked_sub(released)?,
+            total_minted: escrow.total_minted.checked_sub(burned)?,
+        })
+    })?;
+    Ok(())
+}
+
+pub fn next_reply_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = NEXT_REPLY_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_REPLY_ID.save(storage, &(id + 1))?;
+    Ok(id)
+}
+
+// Records an operation under a fresh reply id and returns the id
+pub fn save_pending_op(storage: &mut dyn Storage, op: &PendingOp) -> StdResult<u64> {
+    let id = next_reply_id(storage)?;
+    PENDING_OPS.save(storage, id, op)?;
+    Ok(id)
+}
+
+pub fn token_addr_to_subdenom(api: &dyn Api, token_type: &TokenType, token_addr: &str) -> StdResult<String> {
     match token_type {
         TokenType::ERC20 => {
             // For ERC20, create a subdenom using "crwn" prefix and the token address
-            let addr = if token_addr.starts_with("0x") {
-                token_addr[2..].to_uppercase()
-            } else {
-                token_addr.to_uppercase()
-            };
-            format!("crwn{}", addr)
+            let addr = token_addr.strip_prefix("0x").unwrap_or(token_addr).to_uppercase();
+            Ok(format!("crwn{}", addr))
         },
         TokenType::CW20 => {
-            // For CW20, use base32 encoding of the address
-            let bytes = token_addr.as_bytes();
-            let mut subdenom = base32::encode(BASE32_ALGORITHM, bytes);
-            subdenom.truncate(44);
-            subdenom
+            // For CW20, use "cw20" prefix and base32 encoding of the hashed canonical address.
+            // Tokens registered before this scheme keep the truncated base32 encoding of their
+            // address, which can never start with "cw20".
+            let canonical = api.addr_canonicalize(token_addr)?;
+            let mut subdenom = base32::encode(BASE32_ALGORITHM, &Sha256::digest(canonical.as_slice()));
+            subdenom.truncate(40);
+            Ok(format!("cw20{}", subdenom))
         }
     }
 }
\ No newline at end of file
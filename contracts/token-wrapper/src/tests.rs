@@ -0,0 +1,1058 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, ContractResult, CosmosMsg, OwnedDeps, Reply, Response,
+    StdResult, SubMsgResponse, SubMsgResult, SystemError, SystemResult, Uint128, Uint256, WasmMsg,
+};
+use sei_cosmwasm::{
+    EvmAddressResponse, SeiAddressResponse, SeiMsg, SeiQuery, SeiQueryWrapper, StaticCallResponse,
+};
+
+use crate::abi::{self, ParamType, Token};
+use crate::error::TokenWrapperError;
+use crate::evm::{resolve_evm_recipient, to_checksum_address};
+use crate::fees::{compute_fee, validate_fee_settings, FeeAction, MAX_FEE_BPS};
+use crate::msg::{
+    AmountLimits, DecimalScaling, FeeSettings, MigrateMsg, PauseFlags, ReceiveMsg, RegistrationMode, SolvencyResponse,
+    TokenType, TokenWrapperExecMsg, TokenWrapperQueryMsg, UnwrapRecipient,
+};
+use crate::registry::{decimal_scaling, split_wrapped_amount, to_underlying_amount, to_wrapped_amount};
+use crate::state::{
+    Config, PendingOp, PendingTransfer, PendingWrap, TokenRegistry, ACCRUED_FEES, CONFIG, CW20_TO_DENOM,
+    ERC20_TO_DENOM, ESCROW, PENDING_OPS, TOKEN_INFO,
+};
+
+const WRAP_REPLY_ID: u64 = 7;
+const TOKEN: &str = "0x1111111111111111111111111111111111111111";
+const DENOM: &str = "factory/cosmos2contract/crwn1111111111111111111111111111111111111111";
+const RECIPIENT: &str = "recipient";
+const EVM_RECIPIENT: &str = "0x2222222222222222222222222222222222222222";
+
+// Sei accounts and their associated EVM addresses; "carol" has none
+const ACCOUNTS: [(&str, &str); 2] = [
+    ("alice", "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+    ("bob", "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+];
+const CW20_TOKEN: &str = "cw20token";
+const CW20_DENOM: &str = "factory/cosmos2contract/cw20token";
+const CONTRACT_EVM: &str = "0x9999999999999999999999999999999999999999";
+// The contract's `TOKEN` balance reported by `balanceOf`
+const CONTRACT_BALANCE: u128 = 1_000_000_000_123_456;
+// Alice's `TOKEN` balance and the allowance she gave the contract; bob's allowance is unlimited
+const ALICE_BALANCE: u128 = 10_000;
+const ALICE_ALLOWANCE: u128 = 5_000;
+const BOB_BALANCE: u128 = 2_000;
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<SeiQueryWrapper>, SeiQueryWrapper>;
+
+// Answers the Sei address association queries from `ACCOUNTS`
+fn sei_query_handler(query: &SeiQueryWrapper) -> SystemResult<ContractResult<Binary>> {
+    let response = match &query.query_data {
+        SeiQuery::GetEvmAddress { sei_address } => {
+            let evm_address = ACCOUNTS.iter().find(|(sei, _)| sei == sei_address).map(|(_, evm)| evm.to_string());
+            to_json_binary(&EvmAddressResponse {
+                associated: evm_address.is_some(),
+                evm_address: evm_address.unwrap_or_default(),
+            })
+        },
+        SeiQuery::GetSeiAddress { evm_address } => {
+            let sei_address = ACCOUNTS.iter().find(|(_, evm)| evm.eq_ignore_ascii_case(evm_address)).map(|(sei, _)| sei.to_string());
+            to_json_binary(&SeiAddressResponse {
+                associated: sei_address.is_some(),
+                sei_address: sei_address.unwrap_or_default(),
+            })
+        },
+        SeiQuery::StaticCall { data, .. } => to_json_binary(&StaticCallResponse {
+            encoded_data: Binary::from(erc20_call(&Binary::from_base64(data).unwrap())).to_base64(),
+        }),
+        SeiQuery::Erc20TokenInfo { .. } => to_json_binary(&cw20::TokenInfoResponse {
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            total_supply: Uint128::zero(),
+        }),
+        query => return SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{query:?}") }),
+    };
+    SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+}
+
+// Answers the `balanceOf` and `allowance` calls made to `TOKEN`
+fn erc20_call(data: &[u8]) -> Vec<u8> {
+    let holder = |evm_addr: &str| <[u8; 20]>::try_from(evm_bytes(evm_addr).as_slice()).unwrap();
+    let value = if let Ok([Token::Address(owner)]) =
+        abi::decode_call("balanceOf(address)", &[ParamType::Address], data).as_deref()
+    {
+        match owner {
+            owner if *owner == holder(CONTRACT_EVM) => CONTRACT_BALANCE,
+            owner if *owner == holder(ACCOUNTS[0].1) => ALICE_BALANCE,
+            _ => BOB_BALANCE,
+        }
+    } else {
+        let types = [ParamType::Address, ParamType::Address];
+        match abi::decode_call("allowance(address,address)", &types, data).unwrap().as_slice() {
+            [Token::Address(owner), _] if *owner == holder(ACCOUNTS[0].1) => ALICE_ALLOWANCE,
+            _ => u128::MAX,
+        }
+    };
+    abi::encode(&[Token::Uint(Uint256::from(value))])
+}
+
+fn mock_deps() -> MockDeps {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MockQuerier::new(&[]).with_custom_handler(sei_query_handler),
+        custom_query_type: PhantomData,
+    }
+}
+
+fn abi_bool(value: bool) -> Binary {
+    let mut data = vec![0u8; 32];
+    data[31] = value as u8;
+    Binary::from(data)
+}
+
+fn evm_reply(id: u64, data: Option<Binary>) -> Reply {
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data }),
+    }
+}
+
+// Deps with the contract configured and `TOKEN` registered
+fn deps_with_token() -> MockDeps {
+    let mut deps = mock_deps();
+    CONFIG
+        .save(deps.as_mut().storage, &Config {
+            owner: Some(Addr::unchecked("owner")),
+            pending_owner: None,
+            pauser: None,
+            paused: PauseFlags::default(),
+            registration_mode: RegistrationMode::Permissionless,
+            default_fees: FeeSettings::default(),
+            fee_collector: None,
+            limits: AmountLimits::default(),
+            contract_evm_address: Some(CONTRACT_EVM.to_string()),
+        })
+        .unwrap();
+    ERC20_TO_DENOM.save(deps.as_mut().storage, TOKEN, &DENOM.to_string()).unwrap();
+    TOKEN_INFO
+        .save(deps.as_mut().storage, DENOM, &TokenRegistry {
+            token_type: TokenType::ERC20,
+            token_address: TOKEN.to_string(),
+            wrapped_denom: DENOM.to_string(),
+            fee_on_transfer: false,
+            paused: PauseFlags::default(),
+            fees: None,
+            scaling: None,
+        })
+        .unwrap();
+    deps
+}
+
+fn deps_with_pending_wrap() -> MockDeps {
+    let mut deps = deps_with_token();
+    PENDING_OPS
+        .save(deps.as_mut().storage, WRAP_REPLY_ID, &PendingOp::Wrap(PendingWrap {
+            token_address: TOKEN.to_string(),
+            wrapped_denom: DENOM.to_string(),
+            amount: Uint256::from(1_000u128),
+            recipient: Addr::unchecked(RECIPIENT),
+            balance_before: None,
+            evm_owner: ACCOUNTS[0].1.to_string(),
+        }))
+        .unwrap();
+    deps
+}
+
+fn assert_wrap_minted(data: Option<Binary>) {
+    let mut deps = deps_with_pending_wrap();
+    let res = crate::reply(deps.as_mut(), mock_env(), evm_reply(WRAP_REPLY_ID, data)).unwrap();
+
+    let minted = Coin::new(1_000, DENOM);
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].msg, CosmosMsg::Custom(SeiMsg::MintTokens { amount: minted.clone() }));
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send { to_address: RECIPIENT.to_string(), amount: vec![minted] })
+    );
+    assert!(!PENDING_OPS.has(deps.as_ref().storage, WRAP_REPLY_ID));
+}
+
+fn assert_wrap_rejected(data: Option<Binary>) {
+    let mut deps = deps_with_pending_wrap();
+    let err = crate::reply(deps.as_mut(), mock_env(), evm_reply(WRAP_REPLY_ID, data.clone())).unwrap_err();
+
+    match err {
+        TokenWrapperError::UnexpectedEvmReply(returned) => assert_eq!(Some(returned), data),
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn wrap_reply_accepts_empty_return_data() {
+    assert_wrap_minted(None);
+    assert_wrap_minted(Some(Binary::default()));
+}
+
+#[test]
+fn wrap_reply_accepts_true() {
+    assert_wrap_minted(Some(abi_bool(true)));
+}
+
+#[test]
+fn wrap_reply_rejects_false() {
+    assert_wrap_rejected(Some(abi_bool(false)));
+}
+
+#[test]
+fn wrap_reply_rejects_malformed_return_data() {
+    assert_wrap_rejected(Some(Binary::from(vec![1u8])));
+
+    let mut padded_true = abi_bool(true).to_vec();
+    padded_true.push(0);
+    assert_wrap_rejected(Some(Binary::from(padded_true)));
+
+    let mut dirty_true = abi_bool(true).to_vec();
+    dirty_true[0] = 1;
+    assert_wrap_rejected(Some(Binary::from(dirty_true)));
+}
+
+#[test]
+fn wrap_reply_propagates_evm_failure() {
+    let mut deps = deps_with_pending_wrap();
+    let msg = Reply {
+        id: WRAP_REPLY_ID,
+        result: SubMsgResult::Err("codespace: evm, code: 2".to_string()),
+    };
+
+    let err = crate::reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::FailedReply(_)));
+}
+
+#[test]
+fn wrap_reply_rejects_unknown_id() {
+    let mut deps = mock_deps();
+    let err = crate::reply(deps.as_mut(), mock_env(), evm_reply(WRAP_REPLY_ID, None)).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::InvalidReplyId(WRAP_REPLY_ID)));
+}
+
+fn pending_unwrap(deps: &mut MockDeps, id: u64) {
+    PENDING_OPS
+        .save(deps.as_mut().storage, id, &PendingOp::Unwrap(PendingTransfer {
+            token_address: TOKEN.to_string(),
+            wrapped_denom: DENOM.to_string(),
+            amount: Uint256::from(id as u128),
+            recipient: EVM_RECIPIENT.to_string(),
+        }))
+        .unwrap();
+}
+
+#[test]
+fn unwrap_reply_follows_safe_erc20_semantics() {
+    let mut deps = mock_deps();
+    for (id, data) in [None, Some(Binary::default()), Some(abi_bool(true))].into_iter().enumerate() {
+        let id = id as u64 + 1;
+        pending_unwrap(&mut deps, id);
+        crate::reply(deps.as_mut(), mock_env(), evm_reply(id, data)).unwrap();
+        assert!(!PENDING_OPS.has(deps.as_ref().storage, id));
+    }
+
+    for data in [Some(abi_bool(false)), Some(Binary::from(vec![0u8; 31]))] {
+        pending_unwrap(&mut deps, 10);
+        let err = crate::reply(deps.as_mut(), mock_env(), evm_reply(10, data)).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::UnexpectedEvmReply(_)));
+    }
+}
+
+#[test]
+fn unwrap_replies_are_attributed_per_transfer() {
+    let mut deps = mock_deps();
+    pending_unwrap(&mut deps, 1);
+    pending_unwrap(&mut deps, 2);
+
+    let res = crate::reply(deps.as_mut(), mock_env(), evm_reply(2, None)).unwrap();
+    assert!(!PENDING_OPS.has(deps.as_ref().storage, 2));
+    let event = &res.events[0];
+    assert_eq!(event.ty, "unwrap_erc20_confirmed");
+    let attr = |key: &str| event.attributes.iter().find(|attr| attr.key == key).unwrap().value.clone();
+    assert_eq!(attr("reply_id"), "2");
+    assert_eq!(attr("amount"), "2");
+    assert_eq!(attr("recipient"), EVM_RECIPIENT);
+
+    // The other transfer is still pending and fails on its own. wasmd only passes on the
+    // codespace and code of a failed submessage.
+    assert!(PENDING_OPS.has(deps.as_ref().storage, 1));
+    let msg = Reply {
+        id: 1,
+        result: SubMsgResult::Err("codespace: evm, code: 2".to_string()),
+    };
+    let err = crate::reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::FailedReply(_)));
+}
+
+fn evm_bytes(evm_addr: &str) -> Binary {
+    Binary::from(hex::decode(&evm_addr[2..]).unwrap())
+}
+
+fn wrap_as(deps: &mut MockDeps, caller: &str, evm_sender: Option<&str>) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    let msg = TokenWrapperExecMsg::WrapERC20 {
+        evm_sender: evm_sender.map(evm_bytes),
+        recipient: None,
+        token_addr: TOKEN.to_string(),
+        amount: Uint256::from(1_000u128),
+    };
+    crate::execute(deps.as_mut(), mock_env(), mock_info(caller, &[]), msg)
+}
+
+fn pulled_from(res: &Response<SeiMsg>) -> String {
+    res.attributes.iter().find(|attr| attr.key == "evm_sender").unwrap().value.clone()
+}
+
+#[test]
+fn wrap_pulls_from_the_callers_associated_address() {
+    let mut deps = deps_with_token();
+    let res = wrap_as(&mut deps, "alice", None).unwrap();
+    assert_eq!(pulled_from(&res), ACCOUNTS[0].1);
+}
+
+#[test]
+fn wrap_requires_an_operator_grant_to_pull_from_another_account() {
+    let mut deps = deps_with_token();
+    let bob_evm = Some(ACCOUNTS[1].1);
+
+    let err = wrap_as(&mut deps, "alice", bob_evm).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::UnauthorizedEvmSender(_)));
+
+    let authorize = TokenWrapperExecMsg::AuthorizeWrapOperator { operator: "alice".to_string() };
+    crate::execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), authorize).unwrap();
+    let res = wrap_as(&mut deps, "alice", bob_evm).unwrap();
+    assert_eq!(pulled_from(&res), ACCOUNTS[1].1);
+
+    let revoke = TokenWrapperExecMsg::RevokeWrapOperator { operator: "alice".to_string() };
+    crate::execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), revoke).unwrap();
+    let err = wrap_as(&mut deps, "alice", bob_evm).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::UnauthorizedEvmSender(_)));
+}
+
+#[test]
+fn wrap_rejects_callers_without_an_associated_address() {
+    let mut deps = deps_with_token();
+    let err = wrap_as(&mut deps, "carol", None).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::NoAssociatedEvmAddress(addr) if addr == "carol"));
+}
+
+fn token_metadata(name: &str, symbol: &str) -> Option<sei_cosmwasm::Metadata> {
+    let registry = TOKEN_INFO.load(deps_with_token().as_ref().storage, DENOM).unwrap();
+    let token_info = cw20::TokenInfoResponse {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        decimals: 6,
+        total_supply: Uint128::zero(),
+    };
+    crate::registry::denom_metadata(&registry, &token_info)
+}
+
+#[test]
+fn metadata_display_denom_is_a_valid_denom() {
+    let metadata = token_metadata("Tether USD", "USD₮0").unwrap();
+    assert_eq!(metadata.display, "usd0");
+    assert_eq!(metadata.denom_units[1].denom, "usd0");
+    assert_eq!(metadata.denom_units[1].exponent, 6);
+
+    // Symbols that cannot make a denom display in base units
+    for symbol in ["$1", "0xBTC", "ab"] {
+        let metadata = token_metadata("Token", symbol).unwrap();
+        assert_eq!(metadata.display, DENOM);
+        assert_eq!(metadata.denom_units.len(), 1);
+    }
+}
+
+#[test]
+fn metadata_is_skipped_for_blank_names_and_symbols() {
+    assert!(token_metadata("", "TKN").is_none());
+    assert!(token_metadata("Token", " ").is_none());
+}
+
+fn fee_settings(wrap_bps: u16, min_fee: Option<u128>) -> FeeSettings {
+    FeeSettings {
+        wrap_bps,
+        unwrap_bps: 0,
+        min_fee: min_fee.map(Uint128::new),
+    }
+}
+
+#[test]
+fn fee_rates_are_capped() {
+    assert!(validate_fee_settings(&fee_settings(MAX_FEE_BPS, Some(u128::MAX))).is_ok());
+    assert!(matches!(
+        validate_fee_settings(&fee_settings(MAX_FEE_BPS + 1, None)),
+        Err(TokenWrapperError::InvalidFee(_))
+    ));
+}
+
+#[test]
+fn fee_is_the_rate_with_a_flat_minimum() {
+    let fees = fee_settings(30, Some(500));
+    // 0.3% of 1_000_000
+    assert_eq!(compute_fee(&fees, FeeAction::Wrap, Uint128::new(1_000_000)), Uint128::new(3_000));
+    // 0.3% of 10_000 is below the minimum
+    assert_eq!(compute_fee(&fees, FeeAction::Wrap, Uint128::new(10_000)), Uint128::new(500));
+    // The minimum does not apply to actions without a rate
+    assert_eq!(compute_fee(&fees, FeeAction::Unwrap, Uint128::new(10_000)), Uint128::zero());
+}
+
+#[test]
+fn fee_never_exceeds_the_maximum_rate() {
+    let fees = fee_settings(1, Some(1_000_000));
+    assert_eq!(compute_fee(&fees, FeeAction::Wrap, Uint128::new(1_000_001)), Uint128::new(100_000));
+    assert_eq!(compute_fee(&fees, FeeAction::Wrap, Uint128::new(500)), Uint128::new(50));
+    assert_eq!(compute_fee(&fees, FeeAction::Wrap, Uint128::new(10_000_000)), Uint128::new(1_000_000));
+}
+
+const CONTRACT: &str = "crates.io:sei-token-wrapper";
+
+fn migrate_from(deps: &mut MockDeps, version: &str, msg: MigrateMsg) -> Result<(), TokenWrapperError> {
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT, version).unwrap();
+    crate::migrations::migrate(deps.as_mut(), &mock_env(), msg, CONTRACT, "0.2.0").map(|_| ())
+}
+
+#[test]
+fn migrate_checks_the_contract_name_and_version() {
+    let mut deps = deps_with_token();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+    let err = crate::migrations::migrate(deps.as_mut(), &mock_env(), MigrateMsg::default(), CONTRACT, "0.2.0");
+    assert!(matches!(err, Err(TokenWrapperError::InvalidContractName { .. })));
+
+    let err = migrate_from(&mut deps, "0.3.0", MigrateMsg::default()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::CannotDowngrade { .. }));
+}
+
+#[test]
+fn migrate_runs_only_the_steps_after_the_stored_version() {
+    // A case variant of the token address, as registered before 0.2.0
+    let mixed_case = "0x11111111111111111111111111111111111111aA";
+    let mut deps = deps_with_token();
+    ERC20_TO_DENOM.save(deps.as_mut().storage, mixed_case, &DENOM.to_string()).unwrap();
+
+    migrate_from(&mut deps, "0.2.0", MigrateMsg::default()).unwrap();
+    assert!(ERC20_TO_DENOM.has(deps.as_ref().storage, mixed_case));
+
+    migrate_from(&mut deps, "0.1.0", MigrateMsg::default()).unwrap();
+    assert!(!ERC20_TO_DENOM.has(deps.as_ref().storage, mixed_case));
+    assert!(ESCROW.has(deps.as_ref().storage, DENOM));
+}
+
+#[test]
+fn migrate_cannot_change_the_config_once_ownership_is_renounced() {
+    let mut deps = deps_with_token();
+    let msg = MigrateMsg {
+        registration_mode: Some(RegistrationMode::Allowlist),
+        ..MigrateMsg::default()
+    };
+    migrate_from(&mut deps, "0.2.0", msg.clone()).unwrap();
+    assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap().registration_mode, RegistrationMode::Allowlist);
+
+    CONFIG
+        .update(deps.as_mut().storage, |mut config| -> StdResult<_> {
+            config.owner = None;
+            config.registration_mode = RegistrationMode::Permissionless;
+            Ok(config)
+        })
+        .unwrap();
+    let err = migrate_from(&mut deps, "0.2.0", msg).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::ContractImmutable));
+    // Upgrades without config changes still go through
+    migrate_from(&mut deps, "0.2.0", MigrateMsg::default()).unwrap();
+}
+
+// Test vectors from the EIP-55 specification
+const CHECKSUMMED: [&str; 4] = [
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn evm_recipients_follow_eip55() {
+    let deps = mock_deps();
+    for addr in CHECKSUMMED {
+        let bytes = resolve_evm_recipient(deps.as_ref(), addr).unwrap();
+        assert_eq!(to_checksum_address(&bytes), addr);
+        // Single case addresses carry no checksum
+        assert_eq!(resolve_evm_recipient(deps.as_ref(), &addr.to_lowercase()).unwrap(), bytes);
+    }
+
+    let wrong_checksum = CHECKSUMMED[0].replace("aA", "Aa");
+    assert!(matches!(
+        resolve_evm_recipient(deps.as_ref(), &wrong_checksum),
+        Err(TokenWrapperError::InvalidEvmAddress(_))
+    ));
+}
+
+#[test]
+fn evm_recipients_resolve_sei_addresses_and_base64() {
+    let deps = mock_deps();
+    assert_eq!(resolve_evm_recipient(deps.as_ref(), "alice").unwrap(), [0xaa; 20]);
+
+    // The base64 encoding of these bytes starts with "0x" too
+    let mut bytes = [0x5Au8; 20];
+    bytes[..2].copy_from_slice(&[0xd3, 0x1f]);
+    let encoded = Binary::from(bytes.to_vec()).to_base64();
+    assert!(encoded.starts_with("0x"));
+    assert_eq!(resolve_evm_recipient(deps.as_ref(), &encoded).unwrap(), bytes);
+}
+
+#[test]
+fn tokens_registered_under_an_alias_can_be_unwrapped() {
+    let mut deps = deps_with_token();
+    let env = mock_env();
+    let token = "0x3333333333333333333333333333333333333333";
+    let register = TokenWrapperExecMsg::RegisterToken {
+        token_type: TokenType::ERC20,
+        address: token.to_string(),
+        subdenom: Some("usd/c".to_string()),
+        wrapped_decimals: None,
+    };
+    crate::execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), register).unwrap();
+
+    let denom = format!("factory/{}/usd/c", env.contract.address);
+    crate::state::record_lock(deps.as_mut().storage, &denom, Uint256::from(1_000u128), Uint128::new(1_000)).unwrap();
+    let unwrap = TokenWrapperExecMsg::Unwrap {
+        token_type: None,
+        evm_recipient: None,
+        cosmos_recipient: None,
+        recipients: vec![],
+    };
+    let funds = [Coin::new(1_000, denom.clone())];
+    let res = crate::execute(deps.as_mut(), env, mock_info("alice", &funds), unwrap).unwrap();
+
+    let PendingOp::Unwrap(transfer) = PENDING_OPS.load(deps.as_ref().storage, 1).unwrap() else {
+        panic!("expected a pending unwrap");
+    };
+    assert_eq!(transfer.token_address, token);
+    assert_eq!(transfer.wrapped_denom, denom);
+    assert_eq!(transfer.recipient, ACCOUNTS[0].1);
+    assert_eq!(res.messages.len(), 2);
+}
+
+fn scaling(decimals: u8, wrapped_decimals: u8) -> Option<DecimalScaling> {
+    decimal_scaling(decimals, Some(wrapped_decimals)).unwrap()
+}
+
+// Wraps `DENOM` with 6 of the token's 18 decimals
+fn scale_token(deps: &mut MockDeps) -> TokenRegistry {
+    TOKEN_INFO
+        .update(deps.as_mut().storage, DENOM, |registry| -> StdResult<_> {
+            Ok(TokenRegistry { scaling: scaling(18, 6), ..registry.unwrap() })
+        })
+        .unwrap()
+}
+
+#[test]
+fn decimal_scaling_only_reduces_decimals() {
+    assert_eq!(scaling(18, 6).unwrap().factor, Uint256::from(10u128.pow(12)));
+    assert_eq!(scaling(6, 6), None);
+    assert_eq!(decimal_scaling(6, None).unwrap(), None);
+    assert!(matches!(
+        decimal_scaling(6, Some(8)),
+        Err(TokenWrapperError::InvalidDecimals { decimals: 6, wrapped_decimals: 8 })
+    ));
+
+    // 10^77 is the largest power of ten a Uint256 holds
+    assert!(scaling(77, 0).is_some());
+    assert!(matches!(decimal_scaling(78, Some(0)), Err(TokenWrapperError::InvalidDecimals { .. })));
+}
+
+#[test]
+fn scaled_amounts_convert_both_ways() {
+    let registry = scale_token(&mut deps_with_token());
+    let amount = Uint256::from(1_500_000_000_000_000u128);
+    assert_eq!(to_wrapped_amount(&registry, amount).unwrap(), Uint128::new(1_500));
+    assert_eq!(to_underlying_amount(&registry, Uint128::new(1_500)).unwrap(), amount);
+
+    let with_dust = amount + Uint256::from(42u128);
+    assert_eq!(split_wrapped_amount(&registry, with_dust).unwrap(), (Uint128::new(1_500), Uint256::from(42u128)));
+    assert!(matches!(
+        to_wrapped_amount(&registry, with_dust),
+        Err(TokenWrapperError::AmountNotScalable { .. })
+    ));
+}
+
+#[test]
+fn erc20_wraps_reject_dust() {
+    let mut deps = deps_with_token();
+    scale_token(&mut deps);
+    // 1_000 base units are a fraction of the smallest wrapped unit
+    let err = wrap_as(&mut deps, "alice", None).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::AmountNotScalable { .. }));
+}
+
+// Registers `CW20_TOKEN` under `CW20_DENOM`
+fn register_cw20(deps: &mut MockDeps, scaling: Option<DecimalScaling>) {
+    let token = Addr::unchecked(CW20_TOKEN);
+    CW20_TO_DENOM.save(deps.as_mut().storage, &token, &CW20_DENOM.to_string()).unwrap();
+    TOKEN_INFO
+        .save(deps.as_mut().storage, CW20_DENOM, &TokenRegistry {
+            token_type: TokenType::CW20,
+            token_address: token.to_string(),
+            wrapped_denom: CW20_DENOM.to_string(),
+            fee_on_transfer: false,
+            paused: PauseFlags::default(),
+            fees: None,
+            scaling,
+        })
+        .unwrap();
+}
+
+// Sends `amount` of `CW20_TOKEN` from alice to the contract with the given hook message
+fn receive_cw20(deps: &mut MockDeps, amount: u128, msg: Binary) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    let receive = TokenWrapperExecMsg::Receive(cw20::Cw20ReceiveMsg {
+        sender: "alice".to_string(),
+        amount: Uint128::new(amount),
+        msg,
+    });
+    execute_as(deps, CW20_TOKEN, receive)
+}
+
+fn attribute(res: &Response<SeiMsg>, key: &str) -> String {
+    res.attributes.iter().find(|attr| attr.key == key).unwrap().value.clone()
+}
+
+#[test]
+fn cw20_wraps_reject_dust() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, scaling(8, 6));
+
+    let err = receive_cw20(&mut deps, 150, Binary::default()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::AmountNotScalable { .. }));
+
+    let res = receive_cw20(&mut deps, 200, Binary::default()).unwrap();
+    assert_eq!(attribute(&res, "wrapped_amount"), "2");
+}
+
+#[test]
+fn fee_on_transfer_dust_is_refunded_to_the_evm_owner() {
+    let mut deps = deps_with_token();
+    scale_token(&mut deps);
+    PENDING_OPS
+        .save(deps.as_mut().storage, WRAP_REPLY_ID, &PendingOp::Wrap(PendingWrap {
+            token_address: TOKEN.to_string(),
+            wrapped_denom: DENOM.to_string(),
+            amount: Uint256::from(2_000_000_000_000_000u128),
+            recipient: Addr::unchecked(RECIPIENT),
+            balance_before: Some(Uint256::zero()),
+            evm_owner: ACCOUNTS[0].1.to_string(),
+        }))
+        .unwrap();
+
+    let res = crate::reply(deps.as_mut(), mock_env(), evm_reply(WRAP_REPLY_ID, None)).unwrap();
+    let PendingOp::Refund(refund) = PENDING_OPS.load(deps.as_ref().storage, 1).unwrap() else {
+        panic!("expected a pending refund");
+    };
+    assert_eq!(refund.amount, Uint256::from(123_456u128));
+    assert_eq!(refund.recipient, ACCOUNTS[0].1);
+    assert_eq!(res.messages[0].id, 1);
+    assert_eq!(res.messages[1].msg, CosmosMsg::Custom(SeiMsg::MintTokens { amount: Coin::new(1_000, DENOM) }));
+
+    let escrow = ESCROW.load(deps.as_ref().storage, DENOM).unwrap();
+    assert_eq!(escrow.total_locked, Uint256::from(1_000_000_000_000_000u128));
+    assert_eq!(escrow.total_minted, Uint128::new(1_000));
+}
+
+fn execute_as(
+    deps: &mut MockDeps,
+    sender: &str,
+    msg: TokenWrapperExecMsg,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    crate::execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+}
+
+fn set_fee_collector(deps: &mut MockDeps) {
+    let msg = TokenWrapperExecMsg::SetFeeCollector { collector: Some("collector".to_string()) };
+    execute_as(deps, "owner", msg).unwrap();
+}
+
+fn owner_of(deps: &MockDeps) -> Option<Addr> {
+    CONFIG.load(deps.as_ref().storage).unwrap().owner
+}
+
+#[test]
+fn ownership_moves_in_two_steps() {
+    let mut deps = deps_with_token();
+    let propose = || TokenWrapperExecMsg::ProposeOwner { owner: "bob".to_string() };
+
+    let err = execute_as(&mut deps, "alice", propose()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+    execute_as(&mut deps, "owner", propose()).unwrap();
+    assert_eq!(owner_of(&deps), Some(Addr::unchecked("owner")));
+
+    // Only the proposed owner can accept
+    let err = execute_as(&mut deps, "alice", TokenWrapperExecMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+    execute_as(&mut deps, "bob", TokenWrapperExecMsg::AcceptOwnership {}).unwrap();
+    assert_eq!(owner_of(&deps), Some(Addr::unchecked("bob")));
+    assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap().pending_owner, None);
+
+    let err = execute_as(&mut deps, "owner", propose()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+    let err = execute_as(&mut deps, "bob", TokenWrapperExecMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+}
+
+#[test]
+fn renouncing_ownership_makes_the_contract_immutable() {
+    let mut deps = deps_with_token();
+    let err = execute_as(&mut deps, "alice", TokenWrapperExecMsg::RenounceOwnership {}).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+
+    set_fee_collector(&mut deps);
+    execute_as(&mut deps, "owner", TokenWrapperExecMsg::ProposeOwner { owner: "bob".to_string() }).unwrap();
+    execute_as(&mut deps, "owner", TokenWrapperExecMsg::RenounceOwnership {}).unwrap();
+    assert_eq!(owner_of(&deps), None);
+
+    // Neither the former owner nor a previously proposed one gets back in
+    let err = execute_as(&mut deps, "bob", TokenWrapperExecMsg::AcceptOwnership {}).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+    let admin_msgs = [
+        TokenWrapperExecMsg::ProposeOwner { owner: "owner".to_string() },
+        TokenWrapperExecMsg::SetRegistrationMode { mode: RegistrationMode::Allowlist },
+        TokenWrapperExecMsg::SetLimits { limits: AmountLimits::default() },
+    ];
+    for msg in admin_msgs {
+        let err = execute_as(&mut deps, "owner", msg).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::ContractImmutable));
+    }
+}
+
+fn set_paused(
+    deps: &mut MockDeps,
+    sender: &str,
+    denom: Option<&str>,
+    paused: bool,
+) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    let msg = TokenWrapperExecMsg::SetPaused {
+        denom: denom.map(str::to_string),
+        wrap: None,
+        unwrap: Some(paused),
+    };
+    execute_as(deps, sender, msg)
+}
+
+#[test]
+fn ownership_cannot_be_renounced_while_paused() {
+    let mut deps = deps_with_token();
+    set_fee_collector(&mut deps);
+    for denom in [None, Some(DENOM)] {
+        set_paused(&mut deps, "owner", denom, true).unwrap();
+        let err = execute_as(&mut deps, "owner", TokenWrapperExecMsg::RenounceOwnership {}).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::RenounceWhilePaused));
+        set_paused(&mut deps, "owner", denom, false).unwrap();
+    }
+    execute_as(&mut deps, "owner", TokenWrapperExecMsg::RenounceOwnership {}).unwrap();
+}
+
+fn unwrap_as(deps: &mut MockDeps, sender: &str, funds: &[Coin]) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    let msg = TokenWrapperExecMsg::Unwrap {
+        token_type: None,
+        evm_recipient: None,
+        cosmos_recipient: None,
+        recipients: vec![],
+    };
+    crate::execute(deps.as_mut(), mock_env(), mock_info(sender, funds), msg)
+}
+
+#[test]
+fn paused_operations_are_rejected() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, None);
+    execute_as(&mut deps, "owner", TokenWrapperExecMsg::SetPauser { pauser: Some("pauser".to_string()) }).unwrap();
+
+    let pause_wrap = |paused: bool| TokenWrapperExecMsg::SetPaused { denom: None, wrap: Some(paused), unwrap: None };
+    execute_as(&mut deps, "pauser", pause_wrap(true)).unwrap();
+    let err = receive_cw20(&mut deps, 100, Binary::default()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Paused(action) if action == "wrap"));
+    let err = wrap_as(&mut deps, "alice", None).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Paused(action) if action == "wrap"));
+    execute_as(&mut deps, "pauser", pause_wrap(false)).unwrap();
+
+    // Pausing one denom leaves the others alone
+    set_paused(&mut deps, "pauser", Some(DENOM), true).unwrap();
+    let err = unwrap_as(&mut deps, "alice", &[Coin::new(100, DENOM)]).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Paused(action) if action == "unwrap"));
+    receive_cw20(&mut deps, 100, Binary::default()).unwrap();
+    unwrap_as(&mut deps, "alice", &[Coin::new(100, CW20_DENOM)]).unwrap();
+}
+
+#[test]
+fn pauser_cannot_call_owner_messages() {
+    let mut deps = deps_with_token();
+    execute_as(&mut deps, "owner", TokenWrapperExecMsg::SetPauser { pauser: Some("pauser".to_string()) }).unwrap();
+
+    let owner_msgs = [
+        TokenWrapperExecMsg::SetPauser { pauser: None },
+        TokenWrapperExecMsg::SetLimits { limits: AmountLimits::default() },
+        TokenWrapperExecMsg::SetFeeCollector { collector: Some("pauser".to_string()) },
+        TokenWrapperExecMsg::RenounceOwnership {},
+    ];
+    for msg in owner_msgs {
+        let err = execute_as(&mut deps, "pauser", msg).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::Unauthorized));
+    }
+    let err = set_paused(&mut deps, "alice", None, true).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::Unauthorized));
+}
+
+#[test]
+fn allowlist_mode_only_wraps_registered_tokens() {
+    let mut deps = deps_with_token();
+    let allowlist = TokenWrapperExecMsg::SetRegistrationMode { mode: RegistrationMode::Allowlist };
+    execute_as(&mut deps, "owner", allowlist).unwrap();
+
+    // Registered tokens still wrap, unknown ones are not registered on the fly
+    wrap_as(&mut deps, "alice", None).unwrap();
+    let err = receive_cw20(&mut deps, 100, Binary::default()).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::TokenNotRegistered));
+    let msg = TokenWrapperExecMsg::WrapERC20 {
+        evm_sender: None,
+        recipient: None,
+        token_addr: "0x3333333333333333333333333333333333333333".to_string(),
+        amount: Uint256::from(1_000u128),
+    };
+    let err = execute_as(&mut deps, "alice", msg).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::TokenNotRegistered));
+
+    register_cw20(&mut deps, None);
+    receive_cw20(&mut deps, 100, Binary::default()).unwrap();
+}
+
+#[test]
+fn renouncing_ownership_hands_accrued_fees_to_the_collector() {
+    let mut deps = deps_with_token();
+    ACCRUED_FEES.save(deps.as_mut().storage, DENOM, &Uint128::new(42)).unwrap();
+    let err = execute_as(&mut deps, "owner", TokenWrapperExecMsg::RenounceOwnership {}).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::NoFeeCollector));
+
+    set_fee_collector(&mut deps);
+    let res = execute_as(&mut deps, "owner", TokenWrapperExecMsg::RenounceOwnership {}).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send { to_address: "collector".to_string(), amount: vec![Coin::new(42, DENOM)] })
+    );
+    assert!(!ACCRUED_FEES.has(deps.as_ref().storage, DENOM));
+}
+
+// Makes `CW20_TOKEN` report `balance` for the contract and the wrapped denom a supply of `supply`
+fn set_cw20_backing(deps: &mut MockDeps, balance: u128, supply: u128) {
+    deps.querier.update_wasm(move |_| {
+        let res = to_json_binary(&cw20::BalanceResponse { balance: Uint128::new(balance) });
+        SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+    });
+    deps.querier.update_balance("alice", vec![Coin::new(supply, CW20_DENOM)]);
+}
+
+fn solvency(deps: &MockDeps) -> SolvencyResponse {
+    let msg = TokenWrapperQueryMsg::Solvency { denom: CW20_DENOM.to_string() };
+    from_json(crate::query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn escrow_tracks_cw20_wraps_and_unwraps() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, scaling(8, 6));
+
+    receive_cw20(&mut deps, 1_000, Binary::default()).unwrap();
+    let escrow = ESCROW.load(deps.as_ref().storage, CW20_DENOM).unwrap();
+    assert_eq!((escrow.total_locked, escrow.total_minted), (Uint256::from(1_000u128), Uint128::new(10)));
+
+    unwrap_as(&mut deps, "alice", &[Coin::new(4, CW20_DENOM)]).unwrap();
+    let escrow = ESCROW.load(deps.as_ref().storage, CW20_DENOM).unwrap();
+    assert_eq!((escrow.total_locked, escrow.total_minted), (Uint256::from(600u128), Uint128::new(6)));
+
+    set_cw20_backing(&mut deps, 600, 6);
+    let res = solvency(&deps);
+    assert_eq!(res.total_locked, Uint256::from(600u128));
+    assert_eq!(res.total_minted, Uint128::new(6));
+    assert_eq!(res.bank_supply, Uint128::new(6));
+    assert_eq!(res.underlying_balance, Uint256::from(600u128));
+    assert!(res.solvent);
+
+    // Losing part of the underlying balance leaves the supply under-collateralized
+    set_cw20_backing(&mut deps, 599, 6);
+    assert!(!solvency(&deps).solvent);
+}
+
+fn receive_hook(msg: &ReceiveMsg) -> Binary {
+    to_json_binary(msg).unwrap()
+}
+
+fn delivered(res: &Response<SeiMsg>) -> &CosmosMsg<SeiMsg> {
+    &res.messages[1].msg
+}
+
+#[test]
+fn cw20_receive_delivers_where_the_message_says() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, None);
+    let wrapped = vec![Coin::new(100, CW20_DENOM)];
+
+    // An empty message wraps to the CW20 sender
+    let res = receive_cw20(&mut deps, 100, Binary::default()).unwrap();
+    let send = BankMsg::Send { to_address: "alice".to_string(), amount: wrapped.clone() };
+    assert_eq!(delivered(&res), &CosmosMsg::Bank(send));
+
+    let wrap = ReceiveMsg::Wrap { recipient: Some("bob".to_string()), memo: Some("hi".to_string()) };
+    let res = receive_cw20(&mut deps, 100, receive_hook(&wrap)).unwrap();
+    let send = BankMsg::Send { to_address: "bob".to_string(), amount: wrapped.clone() };
+    assert_eq!(delivered(&res), &CosmosMsg::Bank(send));
+    assert_eq!(attribute(&res, "memo"), "hi");
+
+    let call = Binary::from(br#"{"deposit":{}}"#.to_vec());
+    let wrap_and_call = ReceiveMsg::WrapAndCall { contract: "vault".to_string(), msg: call.clone(), memo: None };
+    let res = receive_cw20(&mut deps, 100, receive_hook(&wrap_and_call)).unwrap();
+    assert_eq!(
+        delivered(&res),
+        &CosmosMsg::Wasm(WasmMsg::Execute { contract_addr: "vault".to_string(), msg: call, funds: wrapped })
+    );
+}
+
+#[test]
+fn cw20_receive_rejects_malformed_messages() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, None);
+
+    // A bare address was the message format before `ReceiveMsg`
+    for msg in [&b"bob"[..], br#"{"wrap_to":{}}"#, br#"{"wrap":{"recipient":5}}"#] {
+        let err = receive_cw20(&mut deps, 100, Binary::from(msg)).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::Std(_)));
+    }
+    assert!(!ESCROW.has(deps.as_ref().storage, CW20_DENOM));
+}
+
+#[test]
+fn cw20_wraps_to_evm_addresses_check_the_checksum() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, None);
+    let wrap_to = |evm_address: String| receive_hook(&ReceiveMsg::WrapToEvm { evm_address, memo: None });
+
+    let checksummed = to_checksum_address(&[0xbb; 20]);
+    for evm_address in [checksummed.clone(), checksummed.to_lowercase()] {
+        let res = receive_cw20(&mut deps, 100, wrap_to(evm_address)).unwrap();
+        assert_eq!(attribute(&res, "recipient"), "bob");
+    }
+
+    let mistyped = checksummed.replacen('B', "b", 1);
+    let err = receive_cw20(&mut deps, 100, wrap_to(mistyped)).unwrap_err();
+    assert!(matches!(err, TokenWrapperError::InvalidEvmAddress(_)));
+}
+
+#[test]
+fn unwraps_route_each_denom_by_its_token_type() {
+    let mut deps = deps_with_token();
+    register_cw20(&mut deps, None);
+    receive_cw20(&mut deps, 300, Binary::default()).unwrap();
+    crate::state::record_lock(deps.as_mut().storage, DENOM, Uint256::from(300u128), Uint128::new(300)).unwrap();
+    let funds = [Coin::new(100, DENOM), Coin::new(200, CW20_DENOM)];
+    let unwrap = |recipients: Vec<UnwrapRecipient>| TokenWrapperExecMsg::Unwrap {
+        token_type: None,
+        evm_recipient: Some(EVM_RECIPIENT.to_string()),
+        cosmos_recipient: Some(Addr::unchecked("bob")),
+        recipients,
+    };
+    let cw20_transfer = |recipient: &str, amount: u128| {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: CW20_TOKEN.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    };
+    let pending_recipient = |deps: &MockDeps, id: u64| match PENDING_OPS.load(deps.as_ref().storage, id).unwrap() {
+        PendingOp::Unwrap(transfer) => transfer.recipient,
+        op => panic!("unexpected pending op {op:?}"),
+    };
+
+    // The ERC20 denom goes to the EVM recipient and the CW20 denom to the Cosmos recipient
+    let res = crate::execute(deps.as_mut(), mock_env(), mock_info("alice", &funds), unwrap(vec![])).unwrap();
+    assert_eq!(pending_recipient(&deps, 1), EVM_RECIPIENT);
+    assert!(res.messages.iter().any(|msg| msg.msg == cw20_transfer("bob", 200)));
+
+    // Overrides apply to their own denom only
+    let overrides = vec![
+        UnwrapRecipient { denom: DENOM.to_string(), recipient: "alice".to_string() },
+        UnwrapRecipient { denom: CW20_DENOM.to_string(), recipient: "carol".to_string() },
+    ];
+    let funds = [Coin::new(100, DENOM), Coin::new(100, CW20_DENOM)];
+    let res = crate::execute(deps.as_mut(), mock_env(), mock_info("alice", &funds), unwrap(overrides)).unwrap();
+    assert_eq!(pending_recipient(&deps, 2), ACCOUNTS[0].1);
+    assert!(res.messages.iter().any(|msg| msg.msg == cw20_transfer("carol", 100)));
+}
+
+#[test]
+fn unwrap_recipient_overrides_must_name_sent_denoms_once() {
+    let mut deps = deps_with_token();
+    let funds = [Coin::new(100, DENOM)];
+    let override_for = |denom: &str| UnwrapRecipient { denom: denom.to_string(), recipient: "bob".to_string() };
+    for recipients in [vec![override_for(CW20_DENOM)], vec![override_for(DENOM), override_for(DENOM)]] {
+        let msg = TokenWrapperExecMsg::Unwrap {
+            token_type: None,
+            evm_recipient: None,
+            cosmos_recipient: None,
+            recipients,
+        };
+        let err = crate::execute(deps.as_mut(), mock_env(), mock_info("alice", &funds), msg).unwrap_err();
+        assert!(matches!(err, TokenWrapperError::InvalidRecipientOverride(_)));
+    }
+}
+
+fn wrap_amount(deps: &mut MockDeps, caller: &str, amount: u128) -> Result<Response<SeiMsg>, TokenWrapperError> {
+    let msg = TokenWrapperExecMsg::WrapERC20 {
+        evm_sender: None,
+        recipient: None,
+        token_addr: TOKEN.to_string(),
+        amount: Uint256::from(amount),
+    };
+    execute_as(deps, caller, msg)
+}
+
+#[test]
+fn wraps_check_the_allowance_and_balance_before_transferring() {
+    let mut deps = deps_with_token();
+    wrap_amount(&mut deps, "alice", ALICE_ALLOWANCE).unwrap();
+
+    match wrap_amount(&mut deps, "alice", ALICE_ALLOWANCE + 1).unwrap_err() {
+        TokenWrapperError::InsufficientAllowance { token, amount, allowance } => {
+            assert_eq!(token, TOKEN);
+            assert_eq!(amount, Uint256::from(ALICE_ALLOWANCE + 1));
+            assert_eq!(allowance, Uint256::from(ALICE_ALLOWANCE));
+        },
+        err => panic!("unexpected error: {err}"),
+    }
+    match wrap_amount(&mut deps, "bob", BOB_BALANCE + 1).unwrap_err() {
+        TokenWrapperError::InsufficientBalance { token, amount, balance } => {
+            assert_eq!(token, TOKEN);
+            assert_eq!(amount, Uint256::from(BOB_BALANCE + 1));
+            assert_eq!(balance, Uint256::from(BOB_BALANCE));
+        },
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn unwraps_check_the_contract_balance_before_transferring() {
+    let mut deps = deps_with_token();
+    let locked = 2 * CONTRACT_BALANCE + 1;
+    crate::state::record_lock(deps.as_mut().storage, DENOM, Uint256::from(locked), Uint128::new(locked)).unwrap();
+
+    unwrap_as(&mut deps, "alice", &[Coin::new(CONTRACT_BALANCE, DENOM)]).unwrap();
+    let err = unwrap_as(&mut deps, "alice", &[Coin::new(CONTRACT_BALANCE + 1, DENOM)]).unwrap_err();
+    assert!(matches!(
+        err,
+        TokenWrapperError::InsufficientBalance { balance, .. } if balance == Uint256::from(CONTRACT_BALANCE)
+    ));
+}
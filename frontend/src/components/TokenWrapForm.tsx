@@ -17,7 +17,7 @@ import {
 } from '@chakra-ui/react';
 import { useWallet } from '../hooks/useWallet';
 import { testnetTokens } from '../config';
-import { TokenType } from '../types/contract';
+import { Cw20HookMsg, TokenType } from '../types/contract';
 import { toContractAmount, hexToBytes, bytesToBase64 } from '../utils/format';
 
 interface TokenWrapFormProps {
@@ -103,14 +103,9 @@ const TokenWrapForm: React.FC<TokenWrapFormProps> = ({ tokenType }) => {
         setRecipient('');
       } else if (tokenType === 'CW20') {
         // For CW20, we need to call the CW20 contract directly
-        // First, encode the recipient if provided
-        let recipientMsg = '';
-        if (recipient) {
-          recipientMsg = recipient;
-        }
-        
-        // Convert recipient to base64 if provided
-        const encodedMsg = recipient ? btoa(recipientMsg) : '';
+        // The wrapper wraps to the sender unless the attached message names a recipient
+        const hookMsg: Cw20HookMsg | undefined = recipient ? { wrap: { recipient } } : undefined;
+        const encodedMsg = hookMsg ? btoa(JSON.stringify(hookMsg)) : '';
         
         // Execute CW20 send message
         const msg = {
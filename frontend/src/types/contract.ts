@@ -24,6 +24,13 @@ export interface ReceiveMsg {
   };
 }
 
+// Attached, JSON encoded in base64, to a CW20 `send` to the wrapper. An empty message wraps to
+// the sender.
+export type Cw20HookMsg =
+  | { wrap: { recipient?: string; memo?: string } }
+  | { wrap_to_evm: { evm_address: string; memo?: string } }
+  | { wrap_and_call: { contract: string; msg: string; memo?: string } };
+
 export interface UnwrapMsg {
   unwrap: {
     token_type: TokenType;